@@ -0,0 +1,360 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ArenaConfig {
+    pub width: f32,
+    pub height: f32,
+    pub paddlepos: f32,
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        ArenaConfig {
+            width: 500.0,
+            height: 800.0,
+            paddlepos: 50.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BallConfig {
+    pub radius: f32,
+    // Speed the ball is launched at, unless the level sets its own
+    pub base_speed: f32,
+    pub max_speed: f32,
+    // Speed added every `paddle_hits_per_step` paddle hits, 0 hits never speeds up
+    pub speed_step: f32,
+    pub paddle_hits_per_step: u32,
+    // Speed added the first time a ball touches the top wall in each life
+    pub top_wall_step: f32,
+    // Smallest speed the ball may travel at across and up or down, so it can never settle
+    // into a loop straight between two walls
+    pub min_horizontal_speed: f32,
+    pub min_vertical_speed: f32,
+    // Seconds without touching a block or the paddle before the ball's angle is nudged by
+    // `stall_nudge` degrees
+    pub stall_time: f32,
+    pub stall_nudge: f32,
+    // Balls bounce off each other instead of passing through
+    pub ball_collisions: bool,
+}
+
+impl Default for BallConfig {
+    fn default() -> Self {
+        BallConfig {
+            radius: 2.5,
+            base_speed: 300.0,
+            max_speed: 600.0,
+            speed_step: 0.0,
+            paddle_hits_per_step: 0,
+            top_wall_step: 0.0,
+            min_horizontal_speed: 0.0,
+            min_vertical_speed: 0.0,
+            stall_time: 10.0,
+            stall_nudge: 15.0,
+            ball_collisions: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PaddleConfig {
+    pub velocity: f32,
+    pub width: f32,
+    pub height: f32,
+    // Outgoing ball angles from vertical, in degrees, for hits at the paddle center and edges
+    pub min_bounce_angle: f32,
+    pub max_bounce_angle: f32,
+    // Fraction of the paddle's speed added to the ball's horizontal speed on contact
+    pub english: f32,
+    // Degrees per second the ball curves for each unit of paddle speed, 0 disables spin
+    pub spin: f32,
+    pub spin_duration: f32,
+}
+
+impl Default for PaddleConfig {
+    fn default() -> Self {
+        PaddleConfig {
+            velocity: 5.0,
+            width: 10.0,
+            height: 5.0,
+            min_bounce_angle: 0.0,
+            max_bounce_angle: 60.0,
+            english: 0.0,
+            spin: 0.0,
+            spin_duration: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum BlockBehaviour {
+    // Always drops this capsule when destroyed, instead of rolling the drop table
+    Drops(PowerUpKind),
+    // Destroys the blocks touching it when it goes, which can set off more of them
+    Explodes,
+}
+
+/// A kind of block that levels can be built from
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BlockType {
+    pub name: String,
+    // The character that stands for this block in a level grid
+    pub symbol: char,
+    pub hits: i32,
+    // Points for destroying it, before any combo
+    pub score: i32,
+    // Sprite for each hit taken, from untouched on. Hits past the end keep the last one.
+    pub sprites: Vec<usize>,
+    // Nothing breaks it, and the level is cleared without it
+    #[serde(default)]
+    pub indestructible: bool,
+    #[serde(default)]
+    pub behaviours: Vec<BlockBehaviour>,
+}
+
+impl BlockType {
+    pub fn has_behaviour(&self, behaviour: BlockBehaviour) -> bool {
+        self.behaviours.contains(&behaviour)
+    }
+
+    /// The sprite of the block once it has taken `hits_taken` hits
+    pub fn sprite(&self, hits_taken: i32) -> usize {
+        let index = (hits_taken.max(0) as usize).min(self.sprites.len().saturating_sub(1));
+        self.sprites.get(index).copied().unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BlockConfig {
+    pub width: f32,
+    pub height: f32,
+    pub types: Vec<BlockType>,
+}
+
+impl BlockConfig {
+    /// Index of the block type a grid character stands for
+    pub fn by_symbol(&self, symbol: char) -> Option<usize> {
+        self.types.iter().position(|block| block.symbol == symbol)
+    }
+
+    /// Index of the block type with this name
+    pub fn by_name(&self, name: &str) -> Option<usize> {
+        self.types.iter().position(|block| block.name == name)
+    }
+}
+
+impl Default for BlockConfig {
+    fn default() -> Self {
+        // A block for every hit count from 1 to 5, which go from sprite 2 up to sprite 6
+        // and come back down as they are hit
+        let names = ["basic", "strong", "hard", "armoured", "reinforced"];
+        let types = (1..=names.len())
+            .map(|hits| BlockType {
+                name: names[hits - 1].to_string(),
+                symbol: std::char::from_digit(hits as u32, 10).unwrap(),
+                hits: hits as i32,
+                score: hits as i32 * 100,
+                sprites: (2..hits + 2).rev().collect(),
+                indestructible: false,
+                behaviours: Vec::new(),
+            })
+            .collect();
+
+        BlockConfig {
+            width: 10.0,
+            height: 5.0,
+            types,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PowerUpKind {
+    WidePaddle,
+    SlowBall,
+    MultiBall,
+    ExtraLife,
+    StickyPaddle,
+    Laser,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PowerUpDrop {
+    pub kind: PowerUpKind,
+    // Chance that a destroyed block drops this capsule
+    pub chance: f32,
+    // Seconds the effect lasts once caught, ignored by instant effects
+    pub duration: f32,
+    pub sprite: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PowerUpConfig {
+    pub width: f32,
+    pub height: f32,
+    pub fall_speed: f32,
+    pub wide_paddle_scale: f32,
+    pub slow_ball_scale: f32,
+    pub multi_ball_count: u32,
+    // Seconds a ball caught by the sticky paddle waits before launching by itself
+    pub sticky_release_time: f32,
+    pub drops: Vec<PowerUpDrop>,
+}
+
+impl PowerUpConfig {
+    pub fn drop(&self, kind: PowerUpKind) -> Option<&PowerUpDrop> {
+        self.drops.iter().find(|drop| drop.kind == kind)
+    }
+
+    // Picks which capsule, if any, a destroyed block drops. `roll` is uniform in [0, 1) and
+    // the chances of the drop table are stacked one after the other.
+    pub fn roll_drop(&self, roll: f32) -> Option<&PowerUpDrop> {
+        let mut cumulative = 0.0;
+        for drop in &self.drops {
+            cumulative += drop.chance;
+            if roll < cumulative {
+                return Some(drop);
+            }
+        }
+        None
+    }
+}
+
+impl Default for PowerUpConfig {
+    fn default() -> Self {
+        PowerUpConfig {
+            width: 20.0,
+            height: 10.0,
+            fall_speed: 100.0,
+            wide_paddle_scale: 1.5,
+            slow_ball_scale: 0.5,
+            multi_ball_count: 2,
+            sticky_release_time: 3.0,
+            drops: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LaserConfig {
+    // Seconds between shots
+    pub fire_interval: f32,
+    pub speed: f32,
+    pub width: f32,
+    pub height: f32,
+    pub sprite: usize,
+}
+
+impl Default for LaserConfig {
+    fn default() -> Self {
+        LaserConfig {
+            fire_interval: 0.3,
+            speed: 400.0,
+            width: 3.0,
+            height: 8.0,
+            sprite: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SimulationConfig {
+    pub tick_rate: u32,
+    pub max_substeps: u32,
+}
+
+impl SimulationConfig {
+    /// Seconds of play in one tick
+    pub fn tick_seconds(&self) -> f32 {
+        1.0 / self.tick_rate.max(1) as f32
+    }
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            tick_rate: 120,
+            max_substeps: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LivesConfig {
+    pub starting: i32,
+    // Lives past this are not awarded, from thresholds or capsules
+    pub max: i32,
+    // Scores that are worth an extra life, lowest first. After the last one another life
+    // comes every `extra_life_every` points, 0 turns that off.
+    pub extra_life_at: Vec<i32>,
+    pub extra_life_every: i32,
+}
+
+impl LivesConfig {
+    /// The first score past `score` that is worth an extra life
+    pub fn next_extra_life(&self, score: i32) -> Option<i32> {
+        if let Some(&threshold) = self.extra_life_at.iter().find(|&&at| at > score) {
+            return Some(threshold);
+        }
+
+        if self.extra_life_every <= 0 {
+            return None;
+        }
+
+        let start = self.extra_life_at.last().copied().unwrap_or(0).max(0);
+        let steps = (score - start).max(0) / self.extra_life_every + 1;
+        Some(start + steps * self.extra_life_every)
+    }
+}
+
+impl Default for LivesConfig {
+    fn default() -> Self {
+        LivesConfig {
+            starting: 3,
+            max: 5,
+            extra_life_at: Vec::new(),
+            extra_life_every: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ScoringConfig {
+    // Every block broken in a row without a ball touching the paddle raises the multiplier
+    // on block points by this much, up to the max
+    pub combo_step: f32,
+    pub max_combo_multiplier: f32,
+    // Clearing a level instantly is worth the whole bonus, which runs down to nothing over
+    // this many seconds
+    pub fast_clear_bonus: i32,
+    pub fast_clear_time: f32,
+    pub life_lost_penalty: i32,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        ScoringConfig {
+            combo_step: 0.1,
+            max_combo_multiplier: 2.0,
+            fast_clear_bonus: 5000,
+            fast_clear_time: 120.0,
+            life_lost_penalty: 500,
+        }
+    }
+}
+
+// Breakout config data
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct BreakoutConfig {
+    pub arena: ArenaConfig,
+    pub ball: BallConfig,
+    pub paddle: PaddleConfig,
+    pub block: BlockConfig,
+    pub powerups: PowerUpConfig,
+    pub laser: LaserConfig,
+    pub lives: LivesConfig,
+    pub scoring: ScoringConfig,
+    pub simulation: SimulationConfig,
+}
@@ -0,0 +1,1310 @@
+use crate::config::{BlockBehaviour, BlockConfig, BreakoutConfig, PowerUpKind};
+use crate::geometry::*;
+use crate::levels::{LevelModifier, LevelsConfig};
+use crate::power_ups::ActivePowerUps;
+use crate::rng::GameRng;
+use crate::scoring::Scoring;
+use crate::speed::BallSpeed;
+
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+// Distance the ball is kept away from a surface after a contact so that the next sweep
+// does not start touching it
+const CONTACT_SKIN: f32 = 0.01;
+
+// Degrees between the balls split off by the multi ball power up
+const MULTI_BALL_SPREAD: f32 = 20.0;
+
+/// Identifies a ball, block, capsule or projectile for as long as it is in play. Ids are
+/// never reused within a game.
+pub type ObjectId = u32;
+
+/// The kind of run a game is part of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum GameMode {
+    Campaign,
+    Replay,
+}
+
+/// Where a game stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Outcome {
+    Playing,
+    Cleared,
+    OutOfLives,
+}
+
+/// What the player is doing during a step
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub struct Input {
+    // Paddle movement, from -1 for full speed left to 1 for full speed right
+    pub paddle: f32,
+    pub launch_ball: bool,
+    pub fire: bool,
+    // Another ball to put into play at the start of the step, from outside the rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spawn_ball: Option<BallSpawn>,
+}
+
+/// Another ball to put into play, already launched. Kept as arrays so that it can be
+/// recorded with the rest of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct BallSpawn {
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+}
+
+#[derive(Debug, Clone)]
+pub struct Paddle {
+    pub position: Vector2<f32>,
+    // Horizontal speed the paddle actually moved at during the last step
+    pub velocity: f32,
+    pub width: f32,
+    pub height: f32,
+    // The paddle can always fire, rather than only while the laser power up runs
+    pub has_laser: bool,
+    // Seconds until the laser can fire again
+    pub laser_cooldown: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Ball {
+    pub id: ObjectId,
+    pub position: Vector2<f32>,
+    pub last_position: Vector2<f32>,
+    pub velocity: Vector2<f32>,
+    pub radius: f32,
+    pub has_launched: bool,
+    // Rate in radians per second the ball's path curves at, for `spin_time` more seconds
+    pub spin: f32,
+    pub spin_time: f32,
+    // While not launched the ball rides on the paddle this far from its center
+    pub paddle_offset: f32,
+    // Seconds until a caught ball launches by itself. The serve waits for the player.
+    pub auto_release: Option<f32>,
+    // Seconds since the ball last touched a block or the paddle
+    pub stall_time: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub id: ObjectId,
+    pub position: Vector2<f32>,
+    pub width: f32,
+    pub height: f32,
+    // Index into the block types of the block config
+    pub block_type: usize,
+    pub max_hits: i32,
+    pub cur_hits: i32,
+    pub indestructible: bool,
+}
+
+impl Block {
+    /// The sprite showing how damaged the block is
+    pub fn sprite(&self, config: &BlockConfig) -> usize {
+        config.types[self.block_type].sprite(self.cur_hits)
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        !self.indestructible && self.cur_hits >= self.max_hits
+    }
+}
+
+/// A falling power up that takes effect when the paddle catches it
+#[derive(Debug, Clone)]
+pub struct Capsule {
+    pub id: ObjectId,
+    pub kind: PowerUpKind,
+    pub position: Vector2<f32>,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// A laser shot fired by the paddle. It flies straight up and damages the first block it
+/// touches.
+#[derive(Debug, Clone)]
+pub struct Projectile {
+    pub id: ObjectId,
+    pub position: Vector2<f32>,
+    pub last_position: Vector2<f32>,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Things that happened during the last step, for anything outside the rules that wants
+/// to react to them
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    BallLaunched {
+        ball: ObjectId,
+    },
+    PaddleHit {
+        ball: ObjectId,
+    },
+    BlockDestroyed {
+        block: ObjectId,
+        max_hits: i32,
+    },
+    PowerUpCaught {
+        kind: PowerUpKind,
+    },
+    LifeLost,
+    LifeAwarded,
+    // A ball or laser shot touched a surface. `normal` points away from the surface and
+    // `point` is where the center of the ball or shot was at the moment of contact.
+    Collision {
+        ball: ObjectId,
+        surface: CollisionSurface,
+        normal: Vector2<f32>,
+        point: Vector2<f32>,
+    },
+}
+
+/// What a ball or laser shot touched, as told to things outside the rules
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CollisionSurface {
+    Wall,
+    Paddle,
+    Block(ObjectId),
+}
+
+/// What a ball or projectile moved into
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Surface {
+    Wall,
+    Paddle,
+    // Index into `Game::blocks`
+    Block(usize),
+}
+
+/// The thing that did the moving, by index into `Game::balls` or `Game::projectiles`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Body {
+    Ball(usize),
+    Projectile(usize),
+}
+
+/// The first surface a body touched during a step. `normal` points away from the surface
+/// and `point` is where the body's center was at the moment of contact.
+#[derive(Debug, Clone, Copy)]
+struct Contact {
+    body: Body,
+    surface: Surface,
+    normal: Vector2<f32>,
+    point: Vector2<f32>,
+}
+
+/// A solid box a body can bounce off
+struct Obstacle {
+    surface: Surface,
+    center: Vector2<f32>,
+    half_extents: Vector2<f32>,
+}
+
+/// The whole state of a game of breakout, one level at a time. Nothing in here knows about
+/// rendering, so it can be stepped as fast as you like without a window.
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub config: BreakoutConfig,
+    pub level_index: usize,
+    pub paddle: Paddle,
+    pub balls: Vec<Ball>,
+    pub blocks: Vec<Block>,
+    pub capsules: Vec<Capsule>,
+    pub projectiles: Vec<Projectile>,
+    pub score: i32,
+    pub lives: i32,
+    pub ball_speed: BallSpeed,
+    pub scoring: Scoring,
+    pub power_ups: ActivePowerUps,
+    pub rng: GameRng,
+    pub events: Vec<GameEvent>,
+    // The score that earns the next extra life, if there is one to earn
+    next_extra_life: Option<i32>,
+    next_id: ObjectId,
+}
+
+impl Game {
+    /// Sets up a level with the ball waiting on the paddle. All randomness comes from `seed`.
+    pub fn new(
+        config: BreakoutConfig,
+        levels: &LevelsConfig,
+        level_index: usize,
+        seed: u64,
+    ) -> Self {
+        let ball_speed = BallSpeed::new(config.ball.base_speed);
+        let lives = config.lives.starting;
+        let next_extra_life = config.lives.next_extra_life(0);
+
+        let mut game = Game {
+            config,
+            level_index,
+            paddle: Paddle {
+                position: Vector2::new(0.0, 0.0),
+                velocity: 0.0,
+                width: 0.0,
+                height: 0.0,
+                has_laser: false,
+                laser_cooldown: 0.0,
+            },
+            balls: Vec::new(),
+            blocks: Vec::new(),
+            capsules: Vec::new(),
+            projectiles: Vec::new(),
+            score: 0,
+            lives,
+            ball_speed,
+            scoring: Scoring::default(),
+            power_ups: ActivePowerUps::default(),
+            rng: GameRng::new(seed),
+            events: Vec::new(),
+            next_extra_life,
+            next_id: 0,
+        };
+
+        game.load_level(levels, level_index);
+        game
+    }
+
+    /// Moves on to the next level once this one is cleared, keeping the score and lives.
+    /// Returns false once the run is over, either out of lives or with the last level
+    /// cleared.
+    pub fn continue_campaign(&mut self, levels: &LevelsConfig) -> bool {
+        match self.outcome() {
+            Outcome::Playing => true,
+            Outcome::Cleared if self.level_index + 1 < levels.levels.len() => {
+                self.load_level(levels, self.level_index + 1);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Lays out a level and puts a fresh paddle and ball in it. Anything left in play from
+    // the last level goes, along with the power ups running on it.
+    fn load_level(&mut self, levels: &LevelsConfig, level_index: usize) {
+        self.level_index = level_index;
+        self.paddle = Paddle {
+            position: Vector2::new(
+                (self.config.paddle.width * 0.5) + (self.config.arena.width * 0.5),
+                self.config.arena.paddlepos,
+            ),
+            velocity: 0.0,
+            width: self.config.paddle.width,
+            height: self.config.paddle.height,
+            has_laser: levels.has_modifier(level_index, LevelModifier::LaserPaddle),
+            laser_cooldown: 0.0,
+        };
+
+        self.ball_speed = BallSpeed::new(
+            levels
+                .ball_speed(level_index)
+                .unwrap_or(self.config.ball.base_speed),
+        );
+
+        self.scoring.reset();
+
+        self.balls.clear();
+        self.blocks.clear();
+        self.capsules.clear();
+        self.projectiles.clear();
+        self.power_ups = ActivePowerUps::default();
+
+        let radius = self.config.ball.radius;
+        let position = Vector2::new(
+            radius + (self.config.arena.width * 0.5),
+            self.config.arena.paddlepos + radius,
+        );
+        self.add_ball(position, Vector2::new(0.0, 0.0), false);
+
+        // Rows are laid out from the top of the arena down
+        let (block_width, block_height) = (self.config.block.width, self.config.block.height);
+        let arena_height = self.config.arena.height;
+
+        let level = &levels.levels[level_index];
+        for (y_pos, x_pos, cell) in level.cells() {
+            // Characters that are not a block type are left out, validation reports them
+            let block_type = match level.block_type(cell, &self.config.block) {
+                Some(block_type) => block_type,
+                None => continue,
+            };
+            let (hits, indestructible) = {
+                let block = &self.config.block.types[block_type];
+                (block.hits, block.indestructible)
+            };
+
+            let id = self.next_id();
+            self.blocks.push(Block {
+                id,
+                position: Vector2::new(
+                    (block_width * 0.5) + block_width * x_pos as f32,
+                    (arena_height - block_height * y_pos as f32) - (block_height * 0.5),
+                ),
+                width: block_width,
+                height: block_height,
+                block_type,
+                max_hits: hits,
+                cur_hits: 0,
+                indestructible,
+            });
+        }
+    }
+
+    /// The blocks left to break, indestructible ones do not count
+    pub fn blocks_remaining(&self) -> i32 {
+        self.blocks
+            .iter()
+            .filter(|block| !block.indestructible)
+            .count() as i32
+    }
+
+    /// The level is over once it is cleared or the last life is gone
+    pub fn is_over(&self) -> bool {
+        self.outcome() != Outcome::Playing
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        if self.blocks_remaining() == 0 {
+            Outcome::Cleared
+        } else if self.lives == 0 {
+            Outcome::OutOfLives
+        } else {
+            Outcome::Playing
+        }
+    }
+
+    /// Advances the game by `delta_seconds`. The events of the step are left in `events`.
+    pub fn step(&mut self, input: &Input, delta_seconds: f32) {
+        self.events.clear();
+
+        if let Some(spawn) = input.spawn_ball {
+            self.spawn_ball(spawn.position.into(), spawn.velocity.into());
+        }
+
+        // The clock on the fast clear bonus stops with the last block
+        if self.blocks_remaining() > 0 {
+            self.scoring.tick(delta_seconds);
+        }
+
+        self.move_paddle(input, delta_seconds);
+        self.move_balls(input, delta_seconds);
+        self.move_projectiles(input, delta_seconds);
+
+        let contacts = self.collide();
+
+        self.bounce(&contacts);
+        self.check_stalls(&contacts, delta_seconds);
+        let destroyed = self.damage_blocks(&contacts);
+        self.update_power_ups(&destroyed, delta_seconds);
+        self.award_extra_lives();
+    }
+
+    fn next_id(&mut self) -> ObjectId {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    // Puts another ball into play, already launched, e.g. for a power up
+    fn spawn_ball(&mut self, position: Vector2<f32>, velocity: Vector2<f32>) {
+        self.add_ball(position, velocity, true)
+    }
+
+    fn add_ball(&mut self, position: Vector2<f32>, velocity: Vector2<f32>, has_launched: bool) {
+        let id = self.next_id();
+        self.balls.push(Ball {
+            id,
+            position,
+            last_position: position,
+            velocity,
+            radius: self.config.ball.radius,
+            has_launched,
+            spin: 0.0,
+            spin_time: 0.0,
+            paddle_offset: 0.0,
+            auto_release: None,
+            stall_time: 0.0,
+        });
+    }
+
+    // Moves the paddle according to the player's input, keeping it inside the arena
+    fn move_paddle(&mut self, input: &Input, delta_seconds: f32) {
+        let paddle = &mut self.paddle;
+        let last_x = paddle.position.x;
+
+        let movement = input.paddle.clamp(-1.0, 1.0);
+        let paddle_x = paddle.position.x + self.config.paddle.velocity * delta_seconds * movement;
+
+        paddle.position.x = paddle_x
+            .max(paddle.width * 0.5)
+            .min(self.config.arena.width - paddle.width * 0.5);
+
+        // Track how fast the paddle really moved, walls included, for the bounce. A step
+        // that takes no time moves nothing.
+        paddle.velocity = if delta_seconds > 0.0 {
+            (paddle.position.x - last_x) / delta_seconds
+        } else {
+            0.0
+        };
+    }
+
+    // Launches, carries and moves the balls, and takes a life when the last one drains
+    fn move_balls(&mut self, input: &Input, delta_seconds: f32) {
+        let speed_scale = if self.power_ups.is_active(PowerUpKind::SlowBall) {
+            self.config.powerups.slow_ball_scale
+        } else {
+            1.0
+        };
+
+        let paddle = &self.paddle;
+        let paddle_top = paddle.position.y + paddle.height * 0.5;
+        let current_speed = self.ball_speed.current;
+
+        for ball in self.balls.iter_mut() {
+            ball.last_position = ball.position;
+
+            // Balls that are not launched ride along on the paddle, caught balls let go by
+            // themselves once their time is up
+            if !ball.has_launched {
+                if let Some(remaining) = ball.auto_release.as_mut() {
+                    *remaining -= delta_seconds;
+                }
+
+                let released = ball.auto_release.is_some_and(|remaining| remaining <= 0.0);
+
+                // Always leave from the paddle, even straight after losing a life
+                ball.position.x = paddle.position.x + ball.paddle_offset;
+                ball.position.y = paddle_top + ball.radius;
+                ball.last_position = ball.position;
+
+                if input.launch_ball || released {
+                    let offset = ball.paddle_offset / (paddle.width * 0.5 + ball.radius);
+                    ball.velocity = paddle_bounce_velocity(
+                        current_speed,
+                        offset,
+                        paddle.velocity,
+                        &self.config.paddle,
+                    );
+                    ball.auto_release = None;
+                    ball.has_launched = true;
+                    self.events.push(GameEvent::BallLaunched { ball: ball.id });
+                }
+            }
+
+            if ball.has_launched {
+                // Curve the ball's path while it still has spin from the paddle
+                if ball.spin_time > 0.0 {
+                    let spin_seconds = ball.spin_time.min(delta_seconds);
+                    ball.velocity = rotate(ball.velocity, ball.spin * spin_seconds);
+                    ball.spin_time -= spin_seconds;
+                }
+
+                // Every ball in play travels at the current speed, which goes up as the
+                // rally goes on
+                let speed = ball.velocity.magnitude();
+                if speed > f32::EPSILON {
+                    ball.velocity *= current_speed / speed;
+                }
+
+                ball.position += ball.velocity * (delta_seconds * speed_scale);
+            }
+        }
+
+        // A life is only lost when the last ball in play drains
+        let mut num_balls = self.balls.len();
+        let mut life_lost = false;
+
+        self.balls.retain(|ball| {
+            let drained = ball.has_launched && ball.position.y < ball.radius;
+
+            if drained && num_balls > 1 {
+                num_balls -= 1;
+                return false;
+            }
+
+            life_lost |= drained;
+            true
+        });
+
+        if life_lost {
+            self.lives = (self.lives - 1).max(0);
+            self.score = (self.score - self.scoring.life_lost(&self.config.scoring)).max(0);
+            self.ball_speed.reset();
+            self.events.push(GameEvent::LifeLost);
+
+            for ball in self.balls.iter_mut() {
+                ball.velocity = Vector2::new(0.0, 0.0);
+                ball.spin_time = 0.0;
+                ball.paddle_offset = 0.0;
+                ball.auto_release = None;
+                ball.has_launched = false;
+            }
+        }
+    }
+
+    // Moves the laser shots already in flight, then fires a pair from the edges of the paddle
+    // if it is armed
+    fn move_projectiles(&mut self, input: &Input, delta_seconds: f32) {
+        let laser = &self.config.laser;
+        let arena_height = self.config.arena.height;
+
+        for projectile in self.projectiles.iter_mut() {
+            projectile.last_position = projectile.position;
+            projectile.position.y += laser.speed * delta_seconds;
+        }
+
+        self.projectiles
+            .retain(|projectile| projectile.position.y <= arena_height + projectile.height);
+
+        let paddle = &mut self.paddle;
+        paddle.laser_cooldown = (paddle.laser_cooldown - delta_seconds).max(0.0);
+
+        let armed = paddle.has_laser || self.power_ups.is_active(PowerUpKind::Laser);
+        if !(input.fire || input.launch_ball) || paddle.laser_cooldown > 0.0 || !armed {
+            return;
+        }
+
+        paddle.laser_cooldown = laser.fire_interval;
+
+        let offset = paddle.width * 0.5 - laser.width;
+        let y = paddle.position.y + paddle.height * 0.5;
+        let muzzles = [
+            Vector2::new(paddle.position.x - offset, y),
+            Vector2::new(paddle.position.x + offset, y),
+        ];
+        let (width, height) = (laser.width, laser.height);
+
+        for &position in muzzles.iter() {
+            let id = self.next_id();
+            self.projectiles.push(Projectile {
+                id,
+                position,
+                last_position: position,
+                width,
+                height,
+            });
+        }
+    }
+
+    // Sweeps every ball and laser projectile along the path it moved this step, stops it at
+    // the first surface it touches and returns what was touched
+    fn collide(&mut self) -> Vec<Contact> {
+        let mut contacts = Vec::new();
+
+        // The paddle comes first, the blocks after it
+        let mut obstacles = vec![Obstacle {
+            surface: Surface::Paddle,
+            center: self.paddle.position,
+            half_extents: Vector2::new(self.paddle.width * 0.5, self.paddle.height * 0.5),
+        }];
+
+        for (i, block) in self.blocks.iter().enumerate() {
+            obstacles.push(Obstacle {
+                surface: Surface::Block(i),
+                center: block.position,
+                half_extents: Vector2::new(block.width * 0.5, block.height * 0.5),
+            });
+        }
+
+        let (arena_width, arena_height) = (self.config.arena.width, self.config.arena.height);
+
+        // The bottom of the arena is left open so the ball can fall out
+        let walls = [
+            (Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)),
+            (Vector2::new(arena_width, 0.0), Vector2::new(-1.0, 0.0)),
+            (Vector2::new(0.0, arena_height), Vector2::new(0.0, -1.0)),
+        ];
+
+        for (i, ball) in self.balls.iter_mut().enumerate() {
+            if !ball.has_launched {
+                continue;
+            }
+
+            let (start, end) = (ball.last_position, ball.position);
+
+            if let Some(hit) = find_earliest_contact(start, end, ball.radius, &walls, &obstacles) {
+                let point = start + (end - start) * hit.time;
+                ball.position = point + hit.normal * CONTACT_SKIN;
+
+                contacts.push(Contact {
+                    body: Body::Ball(i),
+                    surface: hit.surface,
+                    normal: hit.normal,
+                    point,
+                });
+            }
+        }
+
+        // Then let balls knock each other about, once everything else has had its say
+        if self.config.ball.ball_collisions {
+            self.collide_balls();
+        }
+
+        // Laser shots fly straight through the paddle and out of the top, they only hit blocks
+        let block_obstacles = &obstacles[1..];
+
+        for (i, projectile) in self.projectiles.iter_mut().enumerate() {
+            let (start, end) = (projectile.last_position, projectile.position);
+            let radius = projectile.width * 0.5;
+
+            if let Some(hit) = find_earliest_contact(start, end, radius, &[], block_obstacles) {
+                let point = start + (end - start) * hit.time;
+                projectile.position = point + hit.normal * CONTACT_SKIN;
+
+                contacts.push(Contact {
+                    body: Body::Projectile(i),
+                    surface: hit.surface,
+                    normal: hit.normal,
+                    point,
+                });
+            }
+        }
+
+        for contact in &contacts {
+            let ball = match contact.body {
+                Body::Ball(i) => self.balls[i].id,
+                Body::Projectile(i) => self.projectiles[i].id,
+            };
+            let surface = match contact.surface {
+                Surface::Wall => CollisionSurface::Wall,
+                Surface::Paddle => CollisionSurface::Paddle,
+                Surface::Block(i) => CollisionSurface::Block(self.blocks[i].id),
+            };
+
+            self.events.push(GameEvent::Collision {
+                ball,
+                surface,
+                normal: contact.normal,
+                point: contact.point,
+            });
+        }
+
+        contacts
+    }
+
+    // Pushes apart every pair of launched balls that overlap and bounces them off each other
+    fn collide_balls(&mut self) {
+        let mut circles: Vec<_> = self
+            .balls
+            .iter()
+            .filter(|ball| ball.has_launched)
+            .map(|ball| Circle {
+                position: ball.position,
+                velocity: ball.velocity,
+                radius: ball.radius,
+            })
+            .collect();
+
+        for i in 0..circles.len() {
+            let (head, tail) = circles.split_at_mut(i + 1);
+            for other in tail {
+                collide_circles(&mut head[i], other);
+            }
+        }
+
+        let launched = self.balls.iter_mut().filter(|ball| ball.has_launched);
+
+        for (ball, circle) in launched.zip(circles) {
+            ball.position = circle.position;
+            ball.velocity = circle.velocity;
+        }
+    }
+
+    // Bounces the balls off whatever they touched this step
+    fn bounce(&mut self, contacts: &[Contact]) {
+        let sticky = self.power_ups.is_active(PowerUpKind::StickyPaddle);
+
+        for contact in contacts {
+            let ball = match contact.body {
+                Body::Ball(i) => &mut self.balls[i],
+                Body::Projectile(_) => continue,
+            };
+
+            let paddle_top = contact.surface == Surface::Paddle && contact.normal.y > 0.0;
+
+            // Speed up as the rally goes on
+            if paddle_top {
+                self.ball_speed.paddle_hit(&self.config.ball);
+                self.scoring.paddle_hit();
+                self.events.push(GameEvent::PaddleHit { ball: ball.id });
+            } else if contact.surface == Surface::Wall && contact.normal.y < 0.0 {
+                self.ball_speed.top_wall_hit(&self.config.ball);
+            }
+
+            let paddle = &self.paddle;
+
+            if paddle_top && sticky {
+                // A sticky paddle catches the ball where it landed until it is launched again
+                let half_width = paddle.width * 0.5;
+                let offset = contact.point.x - paddle.position.x;
+
+                ball.velocity = Vector2::new(0.0, 0.0);
+                ball.spin_time = 0.0;
+                ball.has_launched = false;
+                ball.paddle_offset = offset.max(-half_width).min(half_width);
+                ball.auto_release = Some(self.config.powerups.sticky_release_time);
+            } else if paddle_top {
+                // The top of the paddle aims the ball based on where it landed and how the
+                // paddle was moving
+                let half_width = paddle.width * 0.5 + ball.radius;
+                let offset = ((contact.point.x - paddle.position.x) / half_width).clamp(-1.0, 1.0);
+
+                let paddle_config = &self.config.paddle;
+                ball.velocity = paddle_bounce_velocity(
+                    self.ball_speed.current,
+                    offset,
+                    paddle.velocity,
+                    paddle_config,
+                );
+
+                // Curve the ball in the direction the paddle was moving
+                ball.spin = -(paddle_config.spin * paddle.velocity).to_radians();
+                ball.spin_time = if ball.spin != 0.0 {
+                    paddle_config.spin_duration
+                } else {
+                    0.0
+                };
+            } else if ball.velocity.dot(&contact.normal) < 0.0 {
+                ball.velocity = reflect(ball.velocity, contact.normal);
+            }
+        }
+    }
+
+    // Breaks the balls out of loops between the walls. A ball that goes too long without
+    // touching a block or the paddle has its angle nudged, and no ball is ever allowed to
+    // travel too close to straight across or straight up.
+    fn check_stalls(&mut self, contacts: &[Contact], delta_seconds: f32) {
+        let config = &self.config.ball;
+
+        // Touching a block or the paddle means the ball is still making progress
+        for contact in contacts {
+            if let (Body::Ball(i), Surface::Paddle) | (Body::Ball(i), Surface::Block(_)) =
+                (contact.body, contact.surface)
+            {
+                self.balls[i].stall_time = 0.0;
+            }
+        }
+
+        for ball in self.balls.iter_mut() {
+            if !ball.has_launched {
+                ball.stall_time = 0.0;
+                continue;
+            }
+
+            ball.stall_time += delta_seconds;
+
+            // Turn away from whichever axis the ball is closer to, so the same loop always
+            // gets the same nudge
+            if ball.stall_time >= config.stall_time {
+                let toward = if ball.velocity.x * ball.velocity.y >= 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+                let away = if ball.velocity.x.abs() > ball.velocity.y.abs() {
+                    -toward
+                } else {
+                    toward
+                };
+
+                ball.velocity = rotate(ball.velocity, away * config.stall_nudge.to_radians());
+                ball.stall_time = 0.0;
+            }
+
+            ball.velocity = clamp_min_components(
+                ball.velocity,
+                config.min_horizontal_speed,
+                config.min_vertical_speed,
+            );
+        }
+    }
+
+    // Takes a hit off every block that was touched, removing the ones that are used up and
+    // any laser shots that hit them. Exploding blocks take the blocks around them along.
+    // Returns the blocks that were destroyed.
+    fn damage_blocks(&mut self, contacts: &[Contact]) -> Vec<Block> {
+        let mut broken = Vec::new();
+        let mut spent_projectiles = Vec::new();
+
+        for contact in contacts {
+            if let Body::Projectile(i) = contact.body {
+                spent_projectiles.push(self.projectiles[i].id);
+            }
+
+            let i = match contact.surface {
+                Surface::Block(i) => i,
+                _ => continue,
+            };
+
+            let block = &mut self.blocks[i];
+            if block.indestructible || block.is_destroyed() {
+                continue;
+            }
+
+            block.cur_hits += 1;
+            if block.is_destroyed() {
+                broken.push(i);
+            }
+        }
+
+        // Blast the neighbours of exploding blocks, which may be set off in turn
+        let mut next = 0;
+        while next < broken.len() {
+            let exploded = &self.blocks[broken[next]];
+            next += 1;
+
+            let block_type = &self.config.block.types[exploded.block_type];
+            if !block_type.has_behaviour(BlockBehaviour::Explodes) {
+                continue;
+            }
+
+            let centre = exploded.position;
+            for (i, block) in self.blocks.iter_mut().enumerate() {
+                let offset = block.position - centre;
+                let touching = offset.x.abs() <= block.width + CONTACT_SKIN
+                    && offset.y.abs() <= block.height + CONTACT_SKIN;
+
+                if touching && !block.indestructible && !block.is_destroyed() {
+                    block.cur_hits = block.max_hits;
+                    broken.push(i);
+                }
+            }
+        }
+
+        let mut destroyed = Vec::new();
+        for i in broken {
+            let block = &self.blocks[i];
+            let points = self.config.block.types[block.block_type].score;
+
+            self.score += self.scoring.block_destroyed(points, &self.config.scoring);
+            self.events.push(GameEvent::BlockDestroyed {
+                block: block.id,
+                max_hits: block.max_hits,
+            });
+            destroyed.push(block.clone());
+        }
+
+        self.blocks.retain(|block| !block.is_destroyed());
+        if !destroyed.is_empty() && self.blocks_remaining() == 0 {
+            self.score += self.scoring.level_cleared(&self.config.scoring);
+        }
+        self.projectiles
+            .retain(|projectile| !spent_projectiles.contains(&projectile.id));
+
+        destroyed
+    }
+
+    // Drops capsules from destroyed blocks, lets them fall and runs the effects of the ones
+    // the paddle catches
+    fn update_power_ups(&mut self, destroyed: &[Block], delta_seconds: f32) {
+        self.power_ups.tick(delta_seconds);
+
+        // Roll the drop table for every block destroyed this step, unless the block always
+        // drops the same thing
+        for block in destroyed {
+            let always_drops = self.config.block.types[block.block_type]
+                .behaviours
+                .iter()
+                .find_map(|behaviour| match behaviour {
+                    BlockBehaviour::Drops(kind) => Some(*kind),
+                    _ => None,
+                });
+
+            let kind = match always_drops {
+                Some(kind) => kind,
+                None => match self.config.powerups.roll_drop(self.rng.next_f32()) {
+                    Some(drop) => drop.kind,
+                    None => continue,
+                },
+            };
+
+            let id = self.next_id();
+            self.capsules.push(Capsule {
+                id,
+                kind,
+                position: block.position,
+                width: self.config.powerups.width,
+                height: self.config.powerups.height,
+            });
+        }
+
+        // Let the capsules fall and see if the paddle caught any
+        let paddle = &self.paddle;
+        let paddle_half_extents = Vector2::new(paddle.width * 0.5, paddle.height * 0.5);
+        let fall = self.config.powerups.fall_speed * delta_seconds;
+        let mut caught = Vec::new();
+
+        for capsule in self.capsules.iter_mut() {
+            capsule.position.y -= fall;
+        }
+
+        self.capsules.retain(|capsule| {
+            let half_extents = Vector2::new(capsule.width * 0.5, capsule.height * 0.5);
+            if rects_overlap(
+                capsule.position,
+                half_extents,
+                paddle.position,
+                paddle_half_extents,
+            ) {
+                caught.push(capsule.kind);
+                return false;
+            }
+
+            capsule.position.y >= -capsule.height
+        });
+
+        for kind in caught {
+            self.events.push(GameEvent::PowerUpCaught { kind });
+            self.apply_power_up(kind);
+        }
+
+        // Keep the paddle size in step with the wide paddle effect
+        let paddle_scale = if self.power_ups.is_active(PowerUpKind::WidePaddle) {
+            self.config.powerups.wide_paddle_scale
+        } else {
+            1.0
+        };
+
+        self.paddle.width = self.config.paddle.width * paddle_scale;
+    }
+
+    // Hands out a life for every threshold the score has passed since the last step
+    fn award_extra_lives(&mut self) {
+        while let Some(threshold) = self.next_extra_life {
+            if self.score < threshold {
+                break;
+            }
+
+            self.award_life();
+            self.next_extra_life = self.config.lives.next_extra_life(threshold);
+        }
+    }
+
+    fn award_life(&mut self) {
+        if self.lives < self.config.lives.max {
+            self.lives += 1;
+            self.events.push(GameEvent::LifeAwarded);
+        }
+    }
+
+    fn apply_power_up(&mut self, kind: PowerUpKind) {
+        match kind {
+            PowerUpKind::ExtraLife => self.award_life(),
+
+            PowerUpKind::MultiBall => {
+                // Split the first ball in play into several heading off at an angle
+                let source = self
+                    .balls
+                    .iter()
+                    .find(|ball| ball.has_launched)
+                    .map(|ball| (ball.velocity, ball.position));
+
+                if let Some((velocity, position)) = source {
+                    for i in 0..self.config.powerups.multi_ball_count {
+                        let step = (i / 2 + 1) as f32 * MULTI_BALL_SPREAD;
+                        let angle = if i % 2 == 0 { step } else { -step };
+
+                        self.spawn_ball(position, rotate(velocity, angle.to_radians()));
+                    }
+                }
+            }
+
+            PowerUpKind::WidePaddle
+            | PowerUpKind::SlowBall
+            | PowerUpKind::StickyPaddle
+            | PowerUpKind::Laser => {
+                if let Some(drop) = self.config.powerups.drop(kind) {
+                    self.power_ups.activate(kind, drop.duration);
+                }
+            }
+        }
+    }
+}
+
+/// The earliest contact found along a path
+struct Hit {
+    time: f32,
+    normal: Vector2<f32>,
+    surface: Surface,
+}
+
+// Sweeps a circle from `start` to `end` against the given walls, each a point and the
+// direction it faces, and every obstacle, then returns the earliest contact
+fn find_earliest_contact(
+    start: Vector2<f32>,
+    end: Vector2<f32>,
+    radius: f32,
+    walls: &[(Vector2<f32>, Vector2<f32>)],
+    obstacles: &[Obstacle],
+) -> Option<Hit> {
+    let wall_hits = walls.iter().filter_map(|&(point, normal)| {
+        sweep_circle_plane(start, end, radius, point, normal).map(|hit| Hit {
+            time: hit.time,
+            normal: hit.normal,
+            surface: Surface::Wall,
+        })
+    });
+
+    let obstacle_hits = obstacles.iter().filter_map(|obstacle| {
+        sweep_circle_aabb(start, end, radius, obstacle.center, obstacle.half_extents).map(|hit| {
+            Hit {
+                time: hit.time,
+                normal: hit.normal,
+                surface: obstacle.surface,
+            }
+        })
+    });
+
+    wall_hits.chain(obstacle_hits).min_by(|a, b| {
+        a.time
+            .partial_cmp(&b.time)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BlockType;
+    use crate::levels::Level;
+
+    const TICK: f32 = 1.0 / 120.0;
+
+    fn grid(rows: &[&str]) -> Level {
+        Level {
+            grid: rows.iter().map(|row| row.to_string()).collect(),
+            ..Level::default()
+        }
+    }
+
+    fn level(rows: &[&str]) -> LevelsConfig {
+        LevelsConfig::new(vec![grid(rows)])
+    }
+
+    #[test]
+    fn nearest_obstacle_is_the_contact() {
+        let obstacle = |surface, y| Obstacle {
+            surface,
+            center: Vector2::new(0.0, y),
+            half_extents: Vector2::new(20.0, 5.0),
+        };
+        let obstacles = [
+            obstacle(Surface::Block(0), 60.0),
+            obstacle(Surface::Block(1), 30.0),
+        ];
+
+        let hit = find_earliest_contact(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(0.0, 100.0),
+            2.5,
+            &[],
+            &obstacles,
+        )
+        .unwrap();
+
+        assert_eq!(hit.surface, Surface::Block(1));
+        assert!((hit.time - 0.225).abs() < 1e-4);
+    }
+
+    #[test]
+    fn launched_ball_leaves_the_paddle() {
+        let mut game = Game::new(BreakoutConfig::default(), &level(&["1"]), 0, 1);
+        let start = game.balls[0].position;
+
+        game.step(
+            &Input {
+                launch_ball: true,
+                ..Input::default()
+            },
+            TICK,
+        );
+        assert!(game.balls[0].has_launched);
+        assert!(game.events.contains(&GameEvent::BallLaunched {
+            ball: game.balls[0].id
+        }));
+
+        for _ in 0..10 {
+            game.step(&Input::default(), TICK);
+        }
+        assert!(game.balls[0].position.y > start.y);
+    }
+
+    #[test]
+    fn spawned_balls_join_the_balls_in_play() {
+        let mut game = Game::new(BreakoutConfig::default(), &level(&["1"]), 0, 1);
+        let input = Input {
+            spawn_ball: Some(BallSpawn {
+                position: [100.0, 100.0],
+                velocity: [0.0, 300.0],
+            }),
+            ..Input::default()
+        };
+
+        game.step(&input, TICK);
+        assert_eq!(game.balls.len(), 2);
+        let ball = &game.balls[1];
+        assert_ne!(ball.id, game.balls[0].id);
+        assert!(ball.has_launched);
+        assert!(ball.position.y > 100.0);
+    }
+
+    #[test]
+    fn steps_that_take_no_time_leave_the_paddle_still() {
+        let mut game = Game::new(BreakoutConfig::default(), &level(&["1"]), 0, 1);
+        let input = Input {
+            paddle: 1.0,
+            launch_ball: true,
+            ..Input::default()
+        };
+
+        game.step(&input, 0.0);
+        assert_eq!(game.paddle.velocity, 0.0);
+        assert!(game.balls[0].velocity.x.is_finite());
+        assert!(game.balls[0].velocity.y.is_finite());
+    }
+
+    #[test]
+    fn last_ball_draining_costs_a_life() {
+        let mut game = Game::new(BreakoutConfig::default(), &level(&["1"]), 0, 1);
+        game.score = 800;
+
+        let ball = &mut game.balls[0];
+        ball.has_launched = true;
+        ball.position = Vector2::new(100.0, ball.radius + 0.1);
+        ball.velocity = Vector2::new(0.0, -300.0);
+
+        game.step(&Input::default(), TICK);
+
+        assert_eq!(game.lives, game.config.lives.starting - 1);
+        assert_eq!(game.score, 800 - game.config.scoring.life_lost_penalty);
+        assert!(!game.balls[0].has_launched);
+        assert!(game.events.contains(&GameEvent::LifeLost));
+    }
+
+    #[test]
+    fn ball_breaks_the_block_it_hits() {
+        let mut game = Game::new(BreakoutConfig::default(), &level(&["1"]), 0, 1);
+        let block = game.blocks[0].clone();
+
+        let ball = &mut game.balls[0];
+        ball.has_launched = true;
+        ball.position = block.position - Vector2::new(0.0, block.height * 0.5 + ball.radius + 1.0);
+        ball.velocity = Vector2::new(0.0, 300.0);
+        let ball_id = ball.id;
+
+        let mut events = Vec::new();
+        for _ in 0..10 {
+            game.step(&Input::default(), TICK);
+            events.append(&mut game.events);
+        }
+
+        assert!(events.iter().any(|event| match event {
+            GameEvent::Collision {
+                ball,
+                surface,
+                normal,
+                ..
+            } => {
+                *ball == ball_id && *surface == CollisionSurface::Block(block.id) && normal.y < 0.0
+            }
+            _ => false,
+        }));
+        assert!(game.blocks.is_empty());
+        let clear_bonus = game.scoring.level_cleared(&game.config.scoring);
+        assert!(clear_bonus > 0);
+        assert_eq!(game.score, 100 + clear_bonus);
+        assert!(game.balls[0].velocity.y < 0.0);
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn exploding_blocks_clear_around_them() {
+        let mut config = BreakoutConfig::default();
+        config.block.types.push(BlockType {
+            name: "bomb".to_string(),
+            symbol: 'B',
+            hits: 1,
+            score: 50,
+            sprites: vec![2],
+            indestructible: false,
+            behaviours: vec![
+                BlockBehaviour::Explodes,
+                BlockBehaviour::Drops(PowerUpKind::ExtraLife),
+            ],
+        });
+        config.block.types.push(BlockType {
+            name: "wall".to_string(),
+            symbol: '#',
+            hits: 1,
+            score: 0,
+            sprites: vec![6],
+            indestructible: true,
+            behaviours: Vec::new(),
+        });
+
+        let mut game = Game::new(config, &level(&["3B.", "#.."]), 0, 1);
+        let bomb = game.blocks[1].clone();
+
+        let ball = &mut game.balls[0];
+        ball.has_launched = true;
+        ball.position = bomb.position - Vector2::new(0.0, bomb.height * 0.5 + ball.radius + 1.0);
+        ball.velocity = Vector2::new(0.0, 300.0);
+
+        for _ in 0..10 {
+            game.step(&Input::default(), TICK);
+            if !game.capsules.is_empty() {
+                break;
+            }
+        }
+
+        assert_eq!(game.blocks.len(), 1);
+        assert!(game.blocks[0].indestructible);
+        assert_eq!(game.blocks_remaining(), 0);
+        assert_eq!(game.outcome(), Outcome::Cleared);
+        assert_eq!(game.capsules[0].kind, PowerUpKind::ExtraLife);
+    }
+
+    #[test]
+    fn score_thresholds_award_lives_up_to_the_max() {
+        let mut config = BreakoutConfig::default();
+        config.lives.extra_life_at = vec![500, 2000];
+        config.lives.extra_life_every = 5000;
+        assert_eq!(config.lives.next_extra_life(2000), Some(7000));
+
+        let mut game = Game::new(config, &level(&["1"]), 0, 1);
+
+        game.score = 600;
+        game.step(&Input::default(), TICK);
+        assert_eq!(game.lives, 4);
+        assert!(game.events.contains(&GameEvent::LifeAwarded));
+
+        game.step(&Input::default(), TICK);
+        assert_eq!(game.lives, 4);
+
+        // Passing several thresholds at once still stops at the max
+        game.score = 12_000;
+        game.step(&Input::default(), TICK);
+        assert_eq!(game.lives, game.config.lives.max);
+        assert_eq!(game.next_extra_life, Some(17_000));
+    }
+
+    #[test]
+    fn clearing_a_level_moves_on_to_the_next() {
+        let levels = LevelsConfig::new(vec![grid(&["1"]), grid(&["12"])]);
+        let mut game = Game::new(BreakoutConfig::default(), &levels, 0, 1);
+        game.blocks.clear();
+        game.score = 10;
+        game.lives = 2;
+
+        assert!(game.continue_campaign(&levels));
+        assert_eq!(game.level_index, 1);
+        assert_eq!(game.blocks_remaining(), 2);
+        assert_eq!((game.score, game.lives), (10, 2));
+        assert!(!game.balls[0].has_launched);
+
+        game.blocks.clear();
+        assert!(!game.continue_campaign(&levels));
+        assert_eq!(game.outcome(), Outcome::Cleared);
+    }
+}
@@ -0,0 +1,406 @@
+use crate::config::PaddleConfig;
+
+use nalgebra::Vector2;
+
+// Two boxes overlap when they overlap on both axes. Touching counts as overlapping.
+pub fn rects_overlap(
+    a_center: Vector2<f32>,
+    a_half_extents: Vector2<f32>,
+    b_center: Vector2<f32>,
+    b_half_extents: Vector2<f32>,
+) -> bool {
+    (a_center.x - b_center.x).abs() <= a_half_extents.x + b_half_extents.x
+        && (a_center.y - b_center.y).abs() <= a_half_extents.y + b_half_extents.y
+}
+
+/// The result of sweeping a circle along a path. `time` is the fraction of the path
+/// travelled before contact, `normal` points away from the surface that was hit.
+#[derive(Debug, Clone, Copy)]
+pub struct SweepHit {
+    pub time: f32,
+    pub normal: Vector2<f32>,
+}
+
+// Sweeps a circle from `start` to `end` against an axis aligned box given by its center
+// and half extents. The space the circle center cannot enter is the box grown by the
+// radius with rounded corners, so this is a ray cast against that rounded box.
+pub fn sweep_circle_aabb(
+    start: Vector2<f32>,
+    end: Vector2<f32>,
+    radius: f32,
+    center: Vector2<f32>,
+    half_extents: Vector2<f32>,
+) -> Option<SweepHit> {
+    let delta = end - start;
+
+    // Already overlapping, only report a hit if we are moving further in
+    let closest = Vector2::new(
+        start
+            .x
+            .max(center.x - half_extents.x)
+            .min(center.x + half_extents.x),
+        start
+            .y
+            .max(center.y - half_extents.y)
+            .min(center.y + half_extents.y),
+    );
+    let offset = start - closest;
+    if offset.norm_squared() < radius * radius {
+        let normal = if offset.norm_squared() > f32::EPSILON {
+            offset.normalize()
+        } else {
+            // The center is inside the box, push out along the shallowest axis
+            let local = start - center;
+            let pen_x = half_extents.x - local.x.abs();
+            let pen_y = half_extents.y - local.y.abs();
+            if pen_x < pen_y {
+                Vector2::new(local.x.signum(), 0.0)
+            } else {
+                Vector2::new(0.0, local.y.signum())
+            }
+        };
+
+        if delta.dot(&normal) < 0.0 {
+            return Some(SweepHit { time: 0.0, normal });
+        }
+        return None;
+    }
+
+    // Slab test against the box grown by the radius. A path that only touches it on the way
+    // out, like a ball launched from resting on the paddle, is not a hit.
+    let grown = half_extents + Vector2::new(radius, radius);
+    let mut t_enter = f32::NEG_INFINITY;
+    let mut t_exit = f32::INFINITY;
+    let mut normal = Vector2::zeros();
+
+    for axis in 0..2 {
+        if delta[axis].abs() < f32::EPSILON {
+            if (start[axis] - center[axis]).abs() > grown[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let t_near =
+            (center[axis] - grown[axis] * delta[axis].signum() - start[axis]) / delta[axis];
+        let t_far = (center[axis] + grown[axis] * delta[axis].signum() - start[axis]) / delta[axis];
+
+        if t_near > t_enter {
+            t_enter = t_near;
+            normal = Vector2::zeros();
+            normal[axis] = -delta[axis].signum();
+        }
+        t_exit = t_exit.min(t_far);
+    }
+
+    if t_enter > t_exit || t_enter > 1.0 || t_exit <= 0.0 {
+        return None;
+    }
+
+    let t_enter = t_enter.max(0.0);
+    let point = start + delta * t_enter;
+    let local = point - center;
+
+    // If the entry point lies past both faces of the original box, the path enters through
+    // one of the rounded corners, which needs a ray vs circle test instead.
+    if local.x.abs() > half_extents.x && local.y.abs() > half_extents.y {
+        let corner = center
+            + Vector2::new(
+                half_extents.x * local.x.signum(),
+                half_extents.y * local.y.signum(),
+            );
+
+        let m = start - corner;
+        let a = delta.dot(&delta);
+        let b = 2.0 * m.dot(&delta);
+        let c = m.dot(&m) - radius * radius;
+        let discriminant = b * b - 4.0 * a * c;
+
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let time = (-b - discriminant.sqrt()) / (2.0 * a);
+        if !(0.0..=1.0).contains(&time) {
+            return None;
+        }
+
+        let normal = (start + delta * time - corner).normalize();
+        return Some(SweepHit { time, normal });
+    }
+
+    Some(SweepHit {
+        time: t_enter,
+        normal,
+    })
+}
+
+// Sweeps a circle from `start` to `end` against an infinite wall through `point` facing
+// along `normal`. The circle is only stopped when it is moving into the wall.
+pub fn sweep_circle_plane(
+    start: Vector2<f32>,
+    end: Vector2<f32>,
+    radius: f32,
+    point: Vector2<f32>,
+    normal: Vector2<f32>,
+) -> Option<SweepHit> {
+    let approach = (end - start).dot(&normal);
+    if approach >= 0.0 {
+        return None;
+    }
+
+    let distance = (start - point).dot(&normal) - radius;
+    let time = (distance / -approach).max(0.0);
+    if time > 1.0 {
+        return None;
+    }
+
+    Some(SweepHit { time, normal })
+}
+
+// Reflects a velocity about a surface normal
+pub fn reflect(velocity: Vector2<f32>, normal: Vector2<f32>) -> Vector2<f32> {
+    velocity - normal * (2.0 * velocity.dot(&normal))
+}
+
+// Rotates a vector counter clockwise by the given angle in radians
+pub fn rotate(vector: Vector2<f32>, angle: f32) -> Vector2<f32> {
+    let (sin, cos) = angle.sin_cos();
+    Vector2::new(
+        vector.x * cos - vector.y * sin,
+        vector.x * sin + vector.y * cos,
+    )
+}
+
+// Keeps each component of a velocity at least the given size, taking the difference from
+// the other component so the speed stays the same. Zero components count as positive.
+pub fn clamp_min_components(velocity: Vector2<f32>, min_x: f32, min_y: f32) -> Vector2<f32> {
+    let speed = velocity.magnitude();
+    let (sign_x, sign_y) = (velocity.x.signum(), velocity.y.signum());
+
+    if velocity.x.abs() < min_x {
+        let x = min_x.min(speed);
+        Vector2::new(sign_x * x, sign_y * (speed * speed - x * x).sqrt())
+    } else if velocity.y.abs() < min_y {
+        let y = min_y.min(speed);
+        Vector2::new(sign_x * (speed * speed - y * y).sqrt(), sign_y * y)
+    } else {
+        velocity
+    }
+}
+
+// Computes the velocity of a ball leaving the paddle. `offset` is where it landed, from -1
+// at the left edge to 1 at the right edge. The center sends the ball out at the minimum
+// angle from vertical and the edges at the maximum. A moving paddle then drags the ball
+// along with it, without going past the maximum angle, always keeping its speed.
+pub fn paddle_bounce_velocity(
+    speed: f32,
+    offset: f32,
+    paddle_velocity: f32,
+    paddle_config: &PaddleConfig,
+) -> Vector2<f32> {
+    let min_angle = paddle_config.min_bounce_angle.to_radians();
+    let max_angle = paddle_config.max_bounce_angle.to_radians();
+
+    let aim = (min_angle + (max_angle - min_angle) * offset.abs()) * offset.signum();
+    let horizontal = speed * aim.sin() + paddle_velocity * paddle_config.english;
+
+    let angle = horizontal
+        .atan2(speed * aim.cos())
+        .max(-max_angle)
+        .min(max_angle);
+
+    Vector2::new(speed * angle.sin(), speed * angle.cos())
+}
+
+/// A moving circle of equal mass to every other, used for ball against ball contacts
+#[derive(Debug, Clone, Copy)]
+pub struct Circle {
+    pub position: Vector2<f32>,
+    pub velocity: Vector2<f32>,
+    pub radius: f32,
+}
+
+// Resolves an elastic collision between two overlapping circles of equal mass. They are
+// pushed apart until they just touch and, if they were moving towards each other, swap the
+// parts of their velocities along the line between their centers. Returns whether they
+// were touching at all.
+pub fn collide_circles(a: &mut Circle, b: &mut Circle) -> bool {
+    let offset = b.position - a.position;
+    let distance = offset.magnitude();
+    let min_distance = a.radius + b.radius;
+
+    if distance >= min_distance {
+        return false;
+    }
+
+    // Circles on top of each other have no line between them, split them sideways
+    let normal = if distance > f32::EPSILON {
+        offset / distance
+    } else {
+        Vector2::new(1.0, 0.0)
+    };
+
+    let push = normal * ((min_distance - distance) * 0.5);
+    a.position -= push;
+    b.position += push;
+
+    let approach = (a.velocity - b.velocity).dot(&normal);
+    if approach > 0.0 {
+        a.velocity -= normal * approach;
+        b.velocity += normal * approach;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circle(x: f32, y: f32, vx: f32, vy: f32) -> Circle {
+        Circle {
+            position: Vector2::new(x, y),
+            velocity: Vector2::new(vx, vy),
+            radius: 5.0,
+        }
+    }
+
+    fn assert_touching_not_overlapping(a: &Circle, b: &Circle) {
+        let distance = (b.position - a.position).magnitude();
+        assert!(
+            distance >= a.radius + b.radius - 1e-4,
+            "circles overlap, {} apart",
+            distance
+        );
+    }
+
+    #[test]
+    fn fast_balls_stop_at_the_entry_face_of_thin_blocks() {
+        // The ball travels 100 units in the step, the block is only 4 thick
+        let hit = sweep_circle_aabb(
+            Vector2::new(0.0, -50.0),
+            Vector2::new(0.0, 50.0),
+            2.5,
+            Vector2::new(0.0, 0.0),
+            Vector2::new(20.0, 2.0),
+        )
+        .unwrap();
+
+        assert!((hit.time - 0.455).abs() < 1e-4);
+        assert_eq!(hit.normal, Vector2::new(0.0, -1.0));
+
+        let hit = sweep_circle_plane(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(0.0, 200.0),
+            2.5,
+            Vector2::new(0.0, 100.0),
+            Vector2::new(0.0, -1.0),
+        )
+        .unwrap();
+
+        assert!((hit.time - 0.4875).abs() < 1e-4);
+        assert_eq!(hit.normal, Vector2::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn corner_hits_push_out_from_the_corner() {
+        let hit = sweep_circle_aabb(
+            Vector2::new(30.0, 30.0),
+            Vector2::new(0.0, 0.0),
+            5.0,
+            Vector2::new(0.0, 0.0),
+            Vector2::new(10.0, 10.0),
+        )
+        .unwrap();
+
+        // Contact when the center is one radius from the corner along the diagonal
+        let contact = 10.0 + 5.0 / 2.0_f32.sqrt();
+        assert!((hit.time - (30.0 - contact) / 30.0).abs() < 1e-4);
+        assert!((hit.normal - Vector2::new(1.0, 1.0).normalize()).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn overlapping_starts_only_hit_when_moving_further_in() {
+        let center = Vector2::new(0.0, 0.0);
+        let half_extents = Vector2::new(10.0, 10.0);
+
+        // Touching the top face
+        let start = Vector2::new(0.0, 13.0);
+        let hit =
+            sweep_circle_aabb(start, Vector2::new(0.0, 3.0), 5.0, center, half_extents).unwrap();
+        assert_eq!(hit.time, 0.0);
+        assert_eq!(hit.normal, Vector2::new(0.0, 1.0));
+        assert!(
+            sweep_circle_aabb(start, Vector2::new(0.0, 23.0), 5.0, center, half_extents).is_none()
+        );
+
+        // With the center inside the box, out through the nearest face
+        let start = Vector2::new(1.0, 8.0);
+        let hit =
+            sweep_circle_aabb(start, Vector2::new(1.0, 0.0), 5.0, center, half_extents).unwrap();
+        assert_eq!(hit.time, 0.0);
+        assert_eq!(hit.normal, Vector2::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn circle_collisions_conserve_momentum() {
+        let pairs = [
+            (circle(0.0, 0.0, 100.0, 0.0), circle(9.0, 0.0, -50.0, 0.0)),
+            (
+                circle(0.0, 0.0, 120.0, 80.0),
+                circle(6.0, 7.0, -30.0, -10.0),
+            ),
+            (circle(0.0, 0.0, 0.0, 300.0), circle(1.0, 9.5, 0.0, 0.0)),
+            (
+                circle(3.0, 3.0, -200.0, 40.0),
+                circle(3.0, 3.0, 200.0, 40.0),
+            ),
+        ];
+
+        for &(mut a, mut b) in pairs.iter() {
+            let before = a.velocity + b.velocity;
+            let energy_before = a.velocity.norm_squared() + b.velocity.norm_squared();
+
+            assert!(collide_circles(&mut a, &mut b));
+
+            let after = a.velocity + b.velocity;
+            let energy_after = a.velocity.norm_squared() + b.velocity.norm_squared();
+
+            assert!((after - before).magnitude() < 1e-3);
+            assert!((energy_after - energy_before).abs() < energy_before * 1e-4 + 1e-3);
+        }
+    }
+
+    #[test]
+    fn circle_collisions_leave_no_overlap() {
+        let pairs = [
+            (circle(0.0, 0.0, 100.0, 0.0), circle(9.9, 0.0, -100.0, 0.0)),
+            (circle(0.0, 0.0, 100.0, 0.0), circle(0.5, 0.2, -100.0, 0.0)),
+            (circle(5.0, 5.0, 0.0, 0.0), circle(5.0, 5.0, 0.0, 0.0)),
+            (
+                circle(0.0, 0.0, 50.0, 50.0),
+                circle(-4.0, -4.0, -50.0, -50.0),
+            ),
+        ];
+
+        for &(mut a, mut b) in pairs.iter() {
+            collide_circles(&mut a, &mut b);
+            assert_touching_not_overlapping(&a, &b);
+
+            // Once apart they must not be heading back into each other
+            let normal = b.position - a.position;
+            assert!((a.velocity - b.velocity).dot(&normal) <= 1e-3);
+        }
+    }
+
+    #[test]
+    fn circles_apart_are_left_alone() {
+        let mut a = circle(0.0, 0.0, 100.0, 0.0);
+        let mut b = circle(20.0, 0.0, -100.0, 0.0);
+
+        assert!(!collide_circles(&mut a, &mut b));
+        assert_eq!(a.velocity, Vector2::new(100.0, 0.0));
+        assert_eq!(b.position, Vector2::new(20.0, 0.0));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::game::GameMode;
+use crate::storage::write_atomic;
+
+use serde::{Deserialize, Serialize};
+
+use std::{fs, io, path::Path};
+
+/// How many scores each table keeps
+pub const TABLE_SIZE: usize = 10;
+
+/// One score in a high score table
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct HighScore {
+    pub name: String,
+    pub score: i32,
+    // Seconds since the Unix epoch
+    pub date: u64,
+    pub level_reached: usize,
+    // Seconds of play
+    pub time: f32,
+}
+
+/// The best scores for runs of one mode started on one level, best first
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HighScoreTable {
+    pub mode: GameMode,
+    pub level: usize,
+    pub entries: Vec<HighScore>,
+}
+
+/// Every high score table, as kept on disk
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct HighScores {
+    pub tables: Vec<HighScoreTable>,
+}
+
+impl HighScores {
+    /// Loads the tables, starting out empty if there is no file yet. A file that cannot be
+    /// read as high scores is an error rather than quietly thrown away.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => ron::de::from_str(&text)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(HighScores::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("Failed to serialize high scores");
+        write_atomic(path, text.as_bytes())
+    }
+
+    /// The scores for runs of `mode` started on `level`, best first
+    pub fn table(&self, mode: GameMode, level: usize) -> &[HighScore] {
+        self.tables
+            .iter()
+            .find(|table| table.mode == mode && table.level == level)
+            .map_or(&[], |table| &table.entries)
+    }
+
+    /// Whether `score` would make it into the table
+    pub fn qualifies(&self, mode: GameMode, level: usize, score: i32) -> bool {
+        let table = self.table(mode, level);
+        score > 0 && (table.len() < TABLE_SIZE || table.iter().any(|entry| score > entry.score))
+    }
+
+    /// Adds a score to its table, dropping the lowest once the table is full. Returns the
+    /// place it got, if it made it in.
+    pub fn insert(&mut self, mode: GameMode, level: usize, entry: HighScore) -> Option<usize> {
+        if !self.qualifies(mode, level, entry.score) {
+            return None;
+        }
+
+        let index = match self
+            .tables
+            .iter()
+            .position(|table| table.mode == mode && table.level == level)
+        {
+            Some(index) => index,
+            None => {
+                self.tables.push(HighScoreTable {
+                    mode,
+                    level,
+                    entries: Vec::new(),
+                });
+                self.tables.len() - 1
+            }
+        };
+
+        // Ties go below the scores that got there first
+        let entries = &mut self.tables[index].entries;
+        let place = entries
+            .iter()
+            .position(|existing| entry.score > existing.score)
+            .unwrap_or(entries.len());
+        entries.insert(place, entry);
+        entries.truncate(TABLE_SIZE);
+
+        Some(place)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, score: i32) -> HighScore {
+        HighScore {
+            name: name.to_string(),
+            score,
+            date: 0,
+            level_reached: 0,
+            time: 0.0,
+        }
+    }
+
+    #[test]
+    fn full_table_keeps_the_best_scores() {
+        let mut scores = HighScores::default();
+        for score in 1..=TABLE_SIZE as i32 {
+            scores.insert(GameMode::Campaign, 0, entry("AAA", score * 10));
+        }
+
+        assert!(!scores.qualifies(GameMode::Campaign, 0, 10));
+        assert_eq!(
+            scores.insert(GameMode::Campaign, 0, entry("BBB", 55)),
+            Some(5)
+        );
+
+        let table = scores.table(GameMode::Campaign, 0);
+        assert_eq!(table.len(), TABLE_SIZE);
+        assert_eq!(table[0].score, TABLE_SIZE as i32 * 10);
+        assert_eq!(table[5].name, "BBB");
+        assert_eq!(table[TABLE_SIZE - 1].score, 20);
+
+        // Other levels and modes have tables of their own
+        assert!(scores.table(GameMode::Campaign, 1).is_empty());
+        assert!(scores.qualifies(GameMode::Replay, 0, 10));
+    }
+}
@@ -0,0 +1,284 @@
+//! Levels as plain text files, one level to a file, which are easier to draw than the grids
+//! in `levels.ron`. A file has a header of metadata and legend lines, a blank line, then the
+//! grid with one character per block:
+//!
+//! ```text
+//! // Lines starting with // in the header are comments
+//! name: Rainbow
+//! author: allora
+//! par_time: 90
+//! ball_speed: 400
+//! modifiers: LaserPaddle
+//! S = reinforced
+//!
+//! ................
+//! .11111111111111.
+//! .SSSSSSSSSSSSSS.
+//! ```
+//!
+//! A legend line gives a grid character the block type of that name, like the legend of a
+//! level in `levels.ron`. Every other header line is optional.
+
+use crate::levels::{Level, LevelModifier, LevelsConfig, LevelsError, EMPTY_CELL};
+
+use std::{fs, io, path::Path, path::PathBuf};
+
+/// Files in a levels directory with this extension are level files
+pub const LEVEL_FILE_EXTENSION: &str = "txt";
+
+/// Reads every level file in `dir`, in the order of their file names. Returns `None` when
+/// the directory is missing or has no level files in it.
+pub fn load_dir(dir: &Path) -> Result<Option<LevelsConfig>, LevelsError> {
+    let mut paths = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    paths.retain(|path| {
+        path.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext == LEVEL_FILE_EXTENSION)
+    });
+    if paths.is_empty() {
+        return Ok(None);
+    }
+    paths.sort();
+
+    let mut levels = Vec::new();
+    for path in paths {
+        let text = fs::read_to_string(&path)?;
+        let level = parse_level(&text).map_err(|(line, message)| LevelsError::Text {
+            path: path.clone(),
+            line,
+            message,
+        })?;
+        levels.push(level);
+    }
+
+    Ok(Some(LevelsConfig::new(levels)))
+}
+
+/// Writes each level to a file of its own in `dir`, named so that they load back in the
+/// same order. Returns the files written.
+pub fn export_dir(levels: &LevelsConfig, dir: &Path) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+
+    // Every index gets as many digits as the last one, so that the files sort in order
+    let last_index = levels.levels.len().saturating_sub(1);
+    let digits = last_index.to_string().len().max(2);
+
+    let mut paths = Vec::new();
+    for (index, level) in levels.levels.iter().enumerate() {
+        let slug: String = level
+            .name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let path = dir.join(format!(
+            "{:0digits$}-{}.{}",
+            index,
+            slug.trim_matches('-'),
+            LEVEL_FILE_EXTENSION,
+            digits = digits
+        ));
+
+        fs::write(&path, write_level(level))?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+/// Reads a level file. Errors come with the line they are on, counting from 1.
+pub fn parse_level(text: &str) -> Result<Level, (usize, String)> {
+    let mut level = Level::default();
+    let mut lines = text.lines().enumerate();
+
+    // The header runs up to the first blank line
+    for (number, line) in lines.by_ref() {
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+        if line.starts_with("//") {
+            continue;
+        }
+
+        parse_header_line(&mut level, line).map_err(|message| (number + 1, message))?;
+    }
+
+    level.grid = lines.map(|(_, line)| line.trim_end().to_string()).collect();
+    while level.grid.last().is_some_and(|row| row.is_empty()) {
+        level.grid.pop();
+    }
+
+    Ok(level)
+}
+
+fn parse_header_line(level: &mut Level, line: &str) -> Result<(), String> {
+    // A legend line is a single character, an equals sign and a block type name
+    let mut chars = line.chars();
+    if let (Some(cell), Some(rest)) = (chars.next(), chars.as_str().trim_start().strip_prefix('='))
+    {
+        if cell == EMPTY_CELL {
+            return Err(format!("'{}' is always an empty cell", EMPTY_CELL));
+        }
+        level.legend.insert(cell, rest.trim().to_string());
+        return Ok(());
+    }
+
+    let (key, value) = match line.find(':') {
+        Some(colon) => (line[..colon].trim(), line[colon + 1..].trim()),
+        None => {
+            return Err(format!(
+                "expected \"key: value\" or a legend, found \"{}\"",
+                line
+            ))
+        }
+    };
+
+    let number = |value: &str| {
+        value
+            .parse::<f32>()
+            .map_err(|_| format!("{} is not a number", value))
+    };
+
+    match key {
+        "name" => level.name = value.to_string(),
+        "author" => level.author = value.to_string(),
+        "par_time" => level.par_time = Some(number(value)?),
+        "ball_speed" => level.ball_speed = Some(number(value)?),
+        "background" => level.background = Some(value.to_string()),
+        "music" => level.music = Some(value.to_string()),
+        "modifiers" => {
+            level.modifiers = value
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| {
+                    ron::de::from_str::<LevelModifier>(name)
+                        .map_err(|_| format!("{} is not a level modifier", name))
+                })
+                .collect::<Result<_, _>>()?;
+        }
+        _ => return Err(format!("unknown header \"{}\"", key)),
+    }
+
+    Ok(())
+}
+
+/// A level as the text of a level file
+pub fn write_level(level: &Level) -> String {
+    let mut text = String::new();
+    let mut header = |key: &str, value: &dyn std::fmt::Display| {
+        text.push_str(&format!("{}: {}\n", key, value));
+    };
+
+    if !level.name.is_empty() {
+        header("name", &level.name);
+    }
+    if !level.author.is_empty() {
+        header("author", &level.author);
+    }
+    if let Some(par_time) = level.par_time {
+        header("par_time", &par_time);
+    }
+    if let Some(ball_speed) = level.ball_speed {
+        header("ball_speed", &ball_speed);
+    }
+    if let Some(background) = &level.background {
+        header("background", background);
+    }
+    if let Some(music) = &level.music {
+        header("music", music);
+    }
+    if !level.modifiers.is_empty() {
+        let modifiers: Vec<_> = level
+            .modifiers
+            .iter()
+            .map(|modifier| ron::ser::to_string(modifier).expect("Failed to write modifier"))
+            .collect();
+        header("modifiers", &modifiers.join(", "));
+    }
+
+    for (cell, name) in &level.legend {
+        text.push_str(&format!("{} = {}\n", cell, name));
+    }
+
+    text.push('\n');
+    for row in &level.grid {
+        text.push_str(row);
+        text.push('\n');
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_files_read_header_and_grid() {
+        let text = "// a test level\n\
+                    name: Test\n\
+                    par_time: 45.5\n\
+                    modifiers: LaserPaddle\n\
+                    S = reinforced\n\
+                    \n\
+                    .1S.\n\
+                    #..#\n\
+                    \n";
+        let level = parse_level(text).unwrap();
+
+        assert_eq!(level.name, "Test");
+        assert_eq!(level.par_time, Some(45.5));
+        assert_eq!(level.modifiers, vec![LevelModifier::LaserPaddle]);
+        assert_eq!(
+            level.legend.get(&'S').map(String::as_str),
+            Some("reinforced")
+        );
+        assert_eq!(level.grid, vec![".1S.", "#..#"]);
+
+        assert_eq!(
+            parse_level("name: x\nspeed: 3\n\n1").unwrap_err(),
+            (2, "unknown header \"speed\"".to_string())
+        );
+    }
+
+    #[test]
+    fn levels_round_trip_through_level_files() {
+        let levels = LevelsConfig::from_ron(include_str!("../../config/levels.ron")).unwrap();
+
+        for level in &levels.levels {
+            assert_eq!(&parse_level(&write_level(level)).unwrap(), level);
+        }
+    }
+
+    #[test]
+    fn exported_levels_load_back_in_order() {
+        let levels = LevelsConfig::new(
+            (0..120)
+                .map(|index| Level {
+                    name: format!("Level {}", index),
+                    grid: vec!["1".to_string()],
+                    ..Level::default()
+                })
+                .collect(),
+        );
+        let dir = std::env::temp_dir().join(format!("breakout-levels-{}", std::process::id()));
+
+        let paths = export_dir(&levels, &dir).unwrap();
+        let loaded = load_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(paths[100].ends_with("100-level-100.txt"));
+        assert!(paths[11].ends_with("011-level-11.txt"));
+        assert_eq!(loaded.unwrap().unwrap().levels, levels.levels);
+    }
+}
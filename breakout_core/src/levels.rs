@@ -0,0 +1,326 @@
+//! The levels of a campaign and the file they are kept in. Files from before levels had
+//! any metadata still load, they are migrated to the current format as they are read.
+
+use crate::config::BlockConfig;
+use crate::level_files;
+
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The version of the level file format written today
+pub const LEVELS_VERSION: u32 = 1;
+
+/// An empty cell in a level grid
+pub const EMPTY_CELL: char = '.';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LevelModifier {
+    // The paddle has its laser for the whole level
+    LaserPaddle,
+}
+
+/// One level of a campaign. Each row of the grid is a string with one character per block,
+/// from the top of the arena down. A character is the symbol of a block type, unless the
+/// legend names the block type it stands for, and `EMPTY_CELL` leaves a gap.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Level {
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    // Seconds a good run takes to clear the level
+    #[serde(default)]
+    pub par_time: Option<f32>,
+    // Ball launch speed on this level, the ball config has it otherwise
+    #[serde(default)]
+    pub ball_speed: Option<f32>,
+    // Asset paths, relative to the assets directory
+    #[serde(default)]
+    pub background: Option<String>,
+    #[serde(default)]
+    pub music: Option<String>,
+    #[serde(default)]
+    pub modifiers: Vec<LevelModifier>,
+    // Grid characters that stand for a block type by name on this level
+    #[serde(default)]
+    pub legend: BTreeMap<char, String>,
+    pub grid: Vec<String>,
+}
+
+impl Level {
+    /// The row, column and character of every cell of the grid that is not empty
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, char)> + '_ {
+        self.grid.iter().enumerate().flat_map(|(row, cells)| {
+            cells
+                .chars()
+                .enumerate()
+                .filter(|&(_, cell)| cell != EMPTY_CELL)
+                .map(move |(column, cell)| (row, column, cell))
+        })
+    }
+
+    /// Index of the block type a grid character stands for on this level
+    pub fn block_type(&self, cell: char, blocks: &BlockConfig) -> Option<usize> {
+        match self.legend.get(&cell) {
+            Some(name) => blocks.by_name(name),
+            None => blocks.by_symbol(cell),
+        }
+    }
+}
+
+// Level data
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LevelsConfig {
+    pub version: u32,
+    pub levels: Vec<Level>,
+}
+
+impl LevelsConfig {
+    pub fn new(levels: Vec<Level>) -> Self {
+        LevelsConfig {
+            version: LEVELS_VERSION,
+            levels,
+        }
+    }
+
+    /// Reads the level files in `levels_dir` if there are any, otherwise the levels file at
+    /// `levels_path`
+    pub fn load_from(levels_dir: &Path, levels_path: &Path) -> Result<Self, LevelsError> {
+        match level_files::load_dir(levels_dir)? {
+            Some(levels) => Ok(levels),
+            None => Self::load(levels_path),
+        }
+    }
+
+    /// Reads a levels file, migrating it from the old format if it is one
+    pub fn load(path: &Path) -> Result<Self, LevelsError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, LevelsError> {
+        let probe: VersionProbe = ron::de::from_str(text)?;
+
+        match probe.version {
+            0 => ron::de::from_str::<LegacyLevelsData>(text)?
+                .levels
+                .migrate(),
+            LEVELS_VERSION => Ok(ron::de::from_str(text)?),
+            version => Err(LevelsError::UnsupportedVersion(version)),
+        }
+    }
+
+    pub fn ball_speed(&self, level_index: usize) -> Option<f32> {
+        self.levels.get(level_index)?.ball_speed
+    }
+
+    pub fn has_modifier(&self, level_index: usize, modifier: LevelModifier) -> bool {
+        self.levels
+            .get(level_index)
+            .is_some_and(|level| level.modifiers.contains(&modifier))
+    }
+
+    /// The name the level goes by, or its index if it has none
+    pub fn name(&self, level_index: usize) -> String {
+        match self.levels.get(level_index) {
+            Some(level) if !level.name.is_empty() => level.name.clone(),
+            _ => format!("LEVEL {}", level_index),
+        }
+    }
+}
+
+impl Default for LevelsConfig {
+    fn default() -> Self {
+        LevelsConfig::new(vec![Level {
+            grid: vec![".".repeat(8); 6],
+            ..Level::default()
+        }])
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelsError {
+    Io(io::Error),
+    Format(ron::de::Error),
+    UnsupportedVersion(u32),
+    // A problem on a line of a level file, counting from 1
+    Text {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    // A block in an old file that there is no character for
+    Unmigratable {
+        level: usize,
+        row: usize,
+        column: usize,
+        value: i32,
+    },
+}
+
+impl fmt::Display for LevelsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelsError::Io(e) => write!(f, "{}", e),
+            LevelsError::Format(e) => write!(f, "not a valid levels file: {}", e),
+            LevelsError::UnsupportedVersion(version) => write!(
+                f,
+                "levels file is version {}, only version {} is supported",
+                version, LEVELS_VERSION
+            ),
+            LevelsError::Text {
+                path,
+                line,
+                message,
+            } => write!(f, "{} line {}: {}", path.display(), line, message),
+            LevelsError::Unmigratable {
+                level,
+                row,
+                column,
+                value,
+            } => write!(
+                f,
+                "level {} row {} column {}: block value {} has no place in the current format",
+                level, row, column, value
+            ),
+        }
+    }
+}
+
+impl Error for LevelsError {}
+
+impl From<io::Error> for LevelsError {
+    fn from(e: io::Error) -> Self {
+        LevelsError::Io(e)
+    }
+}
+
+impl From<ron::de::Error> for LevelsError {
+    fn from(e: ron::de::Error) -> Self {
+        LevelsError::Format(e)
+    }
+}
+
+// Old files have no version, which reads as 0
+#[derive(Deserialize)]
+struct VersionProbe {
+    #[serde(default)]
+    version: u32,
+}
+
+// The unversioned format, a grid of hit counts for each level with the rest kept alongside
+#[derive(Deserialize)]
+struct LegacyLevelsData {
+    levels: LegacyLevelsConfig,
+}
+
+#[derive(Deserialize)]
+struct LegacyLevelsConfig {
+    layout: Vec<Vec<Vec<i32>>>,
+    #[serde(default)]
+    modifiers: Vec<Vec<LevelModifier>>,
+    #[serde(default)]
+    ball_speeds: Vec<f32>,
+}
+
+impl LegacyLevelsConfig {
+    fn migrate(self) -> Result<LevelsConfig, LevelsError> {
+        let mut levels = Vec::new();
+
+        for (level_index, layout) in self.layout.iter().enumerate() {
+            let mut grid = Vec::new();
+
+            for (row, values) in layout.iter().enumerate() {
+                let cells = values
+                    .iter()
+                    .enumerate()
+                    .map(|(column, &value)| match value {
+                        0 => Ok(EMPTY_CELL),
+                        1..=9 => Ok(std::char::from_digit(value as u32, 10).unwrap()),
+                        _ => Err(LevelsError::Unmigratable {
+                            level: level_index,
+                            row,
+                            column,
+                            value,
+                        }),
+                    })
+                    .collect::<Result<String, _>>()?;
+
+                grid.push(cells);
+            }
+
+            levels.push(Level {
+                name: format!("LEVEL {}", level_index),
+                ball_speed: self.ball_speeds.get(level_index).copied(),
+                modifiers: self.modifiers.get(level_index).cloned().unwrap_or_default(),
+                grid,
+                ..Level::default()
+            });
+        }
+
+        Ok(LevelsConfig::new(levels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn old_level_files_are_migrated() {
+        let text = "(levels: (
+            layout: [[[0, 1], [2, 0]], [[3]]],
+            modifiers: [[], [LaserPaddle]],
+            ball_speeds: [300.0],
+        ))";
+        let levels = LevelsConfig::from_ron(text).unwrap();
+
+        assert_eq!(levels.version, LEVELS_VERSION);
+        assert_eq!(levels.levels[0].grid, vec![".1", "2."]);
+        assert_eq!(levels.ball_speed(0), Some(300.0));
+        assert_eq!(levels.ball_speed(1), None);
+        assert!(levels.has_modifier(1, LevelModifier::LaserPaddle));
+
+        let cells: Vec<_> = levels.levels[0].cells().collect();
+        assert_eq!(cells, vec![(0, 1, '1'), (1, 0, '2')]);
+
+        match LevelsConfig::from_ron("(levels: (layout: [[[0, 12]]]))") {
+            Err(LevelsError::Unmigratable { column: 1, .. }) => {}
+            other => panic!("expected a migration error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn current_level_files_load_as_they_are() {
+        let text = "#![enable(implicit_some)]
+        (
+            version: 1,
+            levels: [(
+                name: \"Opener\",
+                author: \"someone\",
+                par_time: 60.0,
+                legend: {'S': \"reinforced\"},
+                grid: [\"1S\"],
+            )],
+        )";
+        let levels = LevelsConfig::from_ron(text).unwrap();
+
+        assert_eq!(levels.name(0), "Opener");
+        assert_eq!(levels.levels[0].par_time, Some(60.0));
+        assert_eq!(levels.name(1), "LEVEL 1");
+
+        let (level, blocks) = (&levels.levels[0], BlockConfig::default());
+        assert_eq!(level.block_type('1', &blocks), Some(0));
+        assert_eq!(level.block_type('S', &blocks), Some(4));
+        assert_eq!(level.block_type('x', &blocks), None);
+
+        match LevelsConfig::from_ron("(version: 9, levels: [])") {
+            Err(LevelsError::UnsupportedVersion(9)) => {}
+            other => panic!("expected a version error, got {:?}", other),
+        }
+    }
+}
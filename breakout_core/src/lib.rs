@@ -0,0 +1,23 @@
+//! The rules of breakout as plain Rust, with no engine attached. The game binary drives a
+//! `Game` from its systems and draws what it finds in there, anything else can step it on
+//! its own.
+
+pub mod config;
+pub mod game;
+pub mod geometry;
+pub mod high_scores;
+pub mod level_files;
+pub mod levels;
+pub mod power_ups;
+pub mod replay;
+pub mod rng;
+pub mod scoring;
+pub mod speed;
+pub mod storage;
+pub mod validation;
+
+pub use self::game::{
+    Ball, BallSpawn, Block, Capsule, CollisionSurface, Game, GameEvent, GameMode, Input, ObjectId,
+    Outcome, Paddle, Projectile,
+};
+pub use self::replay::{Replay, ReplayPlayer};
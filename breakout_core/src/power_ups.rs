@@ -0,0 +1,28 @@
+use crate::config::PowerUpKind;
+
+/// The timed power up effects currently running and how many seconds each has left
+#[derive(Debug, Clone, Default)]
+pub struct ActivePowerUps {
+    pub timers: Vec<(PowerUpKind, f32)>,
+}
+
+impl ActivePowerUps {
+    /// Starts an effect, or tops up its time if it is already running
+    pub fn activate(&mut self, kind: PowerUpKind, duration: f32) {
+        match self.timers.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, remaining)) => *remaining = remaining.max(duration),
+            None => self.timers.push((kind, duration)),
+        }
+    }
+
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.timers.iter().any(|(k, _)| *k == kind)
+    }
+
+    pub fn tick(&mut self, delta_seconds: f32) {
+        for (_, remaining) in self.timers.iter_mut() {
+            *remaining -= delta_seconds;
+        }
+        self.timers.retain(|(_, remaining)| *remaining > 0.0);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::config::BreakoutConfig;
+use crate::game::{Game, Input};
+use crate::levels::LevelsConfig;
+use crate::storage::write_atomic;
+
+use serde::{Deserialize, Serialize};
+
+use std::{error::Error, fmt, fs, io, path::Path};
+
+/// Everything needed to play a run again tick for tick: the first level, the seed and what the
+/// player did on every tick. The game is only deterministic for the config it was recorded
+/// with, so the replay carries a hash of that as well.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Replay {
+    pub config_hash: u64,
+    pub level: usize,
+    pub seed: u64,
+    // The input of every tick in order, with repeats of the same input stored once
+    pub inputs: Vec<InputRun>,
+}
+
+/// The same input held for a number of ticks
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct InputRun {
+    pub ticks: u32,
+    pub input: Input,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Format(ron::de::Error),
+    // The replay was recorded with a different config or level layout than the one given
+    ConfigMismatch { expected: u64, found: u64 },
+    MissingLevel(usize),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "{}", e),
+            ReplayError::Format(e) => write!(f, "not a valid replay: {}", e),
+            ReplayError::ConfigMismatch { expected, found } => write!(
+                f,
+                "replay was recorded with config {:016x}, this is config {:016x}",
+                found, expected
+            ),
+            ReplayError::MissingLevel(level) => write!(f, "there is no level {}", level),
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(e: io::Error) -> Self {
+        ReplayError::Io(e)
+    }
+}
+
+impl From<ron::de::Error> for ReplayError {
+    fn from(e: ron::de::Error) -> Self {
+        ReplayError::Format(e)
+    }
+}
+
+/// Fingerprint of the rules and levels a run is played with. Any change to either can
+/// change how a run plays out, so replays only play back under the same hash.
+pub fn config_hash(config: &BreakoutConfig, levels: &LevelsConfig) -> u64 {
+    let text = ron::ser::to_string(&(config, levels)).expect("Failed to serialize config");
+
+    // FNV-1a, which is stable across builds unlike the std hasher
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl Replay {
+    pub fn new(config_hash: u64, level: usize, seed: u64) -> Self {
+        Replay {
+            config_hash,
+            level,
+            seed,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Adds the input of the next tick
+    pub fn record(&mut self, input: Input) {
+        match self.inputs.last_mut() {
+            Some(run) if run.input == input && run.ticks < u32::MAX => run.ticks += 1,
+            _ => self.inputs.push(InputRun { ticks: 1, input }),
+        }
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.inputs.iter().map(|run| u64::from(run.ticks)).sum()
+    }
+
+    /// Sets up the game the replay starts from, as long as it was recorded with these
+    /// rules and levels
+    pub fn start(
+        &self,
+        config: BreakoutConfig,
+        levels: &LevelsConfig,
+    ) -> Result<Game, ReplayError> {
+        let expected = config_hash(&config, levels);
+        if expected != self.config_hash {
+            return Err(ReplayError::ConfigMismatch {
+                expected,
+                found: self.config_hash,
+            });
+        }
+
+        if self.level >= levels.levels.len() {
+            return Err(ReplayError::MissingLevel(self.level));
+        }
+
+        Ok(Game::new(config, levels, self.level, self.seed))
+    }
+
+    /// Plays the whole replay through with these rules and levels, moving through the
+    /// campaign the same way the game does and stopping early if the run ends before the
+    /// recording does
+    pub fn play(&self, config: BreakoutConfig, levels: &LevelsConfig) -> Result<Game, ReplayError> {
+        let delta_seconds = config.simulation.tick_seconds();
+        let mut game = self.start(config, levels)?;
+
+        for run in &self.inputs {
+            for _ in 0..run.ticks {
+                if !game.continue_campaign(levels) {
+                    return Ok(game);
+                }
+                game.step(&run.input, delta_seconds);
+            }
+        }
+
+        Ok(game)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let text = fs::read_to_string(path)?;
+        Ok(ron::de::from_str(&text)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        let text = ron::ser::to_string(self).expect("Failed to serialize replay");
+        write_atomic(path, text.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Hands out the inputs of a replay one tick at a time
+#[derive(Debug, Clone)]
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    run: usize,
+    tick: u32,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        ReplayPlayer {
+            replay,
+            run: 0,
+            tick: 0,
+        }
+    }
+
+    /// The input of the next tick, or `None` once the recording is over
+    pub fn next_input(&mut self) -> Option<Input> {
+        while let Some(run) = self.replay.inputs.get(self.run) {
+            if self.tick < run.ticks {
+                self.tick += 1;
+                return Some(run.input);
+            }
+
+            self.run += 1;
+            self.tick = 0;
+        }
+
+        None
+    }
+
+    pub fn is_finished(&self) -> bool {
+        let mut runs = self.replay.inputs.iter().skip(self.run);
+        runs.next().map_or(true, |run| self.tick >= run.ticks) && runs.all(|run| run.ticks == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::BallSpawn;
+
+    fn load_config() -> (BreakoutConfig, LevelsConfig) {
+        let config = include_str!("../../config/breakout.ron");
+        let levels = include_str!("../../config/levels.ron");
+        (
+            ron::de::from_str(config).unwrap(),
+            LevelsConfig::from_ron(levels).unwrap(),
+        )
+    }
+
+    #[test]
+    fn replay_reproduces_the_run() {
+        let (config, levels) = load_config();
+        let delta_seconds = config.simulation.tick_seconds();
+        let mut replay = Replay::new(config_hash(&config, &levels), 0, 7);
+        let mut game = replay.start(config.clone(), &levels).unwrap();
+
+        // Sweep the paddle back and forth, relaunching whenever the ball is lost
+        for tick in 0..6000 {
+            if !game.continue_campaign(&levels) {
+                break;
+            }
+
+            // Put an extra ball into play now and then as well
+            let spawn_ball = if tick % 1000 == 500 {
+                Some(BallSpawn {
+                    position: [200.0, 300.0],
+                    velocity: [150.0, 250.0],
+                })
+            } else {
+                None
+            };
+
+            let input = Input {
+                paddle: if (tick / 90) % 2 == 0 { 1.0 } else { -1.0 },
+                launch_ball: tick % 200 == 0,
+                fire: true,
+                spawn_ball,
+            };
+            replay.record(input);
+            game.step(&input, delta_seconds);
+        }
+
+        let text = ron::ser::to_string(&replay).unwrap();
+        let replay: Replay = ron::de::from_str(&text).unwrap();
+
+        let replayed = replay.play(config, &levels).unwrap();
+        assert_eq!(replayed.score, game.score);
+        assert_eq!(replayed.lives, game.lives);
+        assert_eq!(replayed.blocks_remaining(), game.blocks_remaining());
+        assert_eq!(replayed.paddle.position, game.paddle.position);
+        assert_eq!(replayed.balls.len(), game.balls.len());
+    }
+
+    #[test]
+    fn replay_refuses_other_config() {
+        let (mut config, levels) = load_config();
+        let replay = Replay::new(config_hash(&config, &levels), 0, 7);
+
+        config.ball.base_speed += 1.0;
+        match replay.start(config, &levels) {
+            Err(ReplayError::ConfigMismatch { .. }) => {}
+            other => panic!("expected a config mismatch, got {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+/// A small xorshift random number generator. Gameplay randomness goes through this so that
+/// a run can be reproduced from its seed.
+#[derive(Debug, Clone)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on a zero state
+        GameRng {
+            state: if seed == 0 {
+                0x9e37_79b9_7f4a_7c15
+            } else {
+                seed
+            },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng::new(0)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::config::ScoringConfig;
+
+/// Keeps track of what the next points are worth: the combo of blocks broken since a ball
+/// last touched the paddle, and how long the level has taken so far.
+#[derive(Debug, Clone, Default)]
+pub struct Scoring {
+    pub combo: u32,
+    pub level_time: f32,
+}
+
+impl Scoring {
+    pub fn tick(&mut self, delta_seconds: f32) {
+        self.level_time += delta_seconds;
+    }
+
+    /// Points for breaking a block worth `base` points, counting it towards the combo
+    pub fn block_destroyed(&mut self, base: i32, config: &ScoringConfig) -> i32 {
+        self.combo += 1;
+        (base as f32 * self.multiplier(config)).round() as i32
+    }
+
+    /// The multiplier on the next block broken
+    pub fn multiplier(&self, config: &ScoringConfig) -> f32 {
+        let step = config.combo_step * self.combo.saturating_sub(1) as f32;
+        (1.0 + step).min(config.max_combo_multiplier.max(1.0))
+    }
+
+    pub fn paddle_hit(&mut self) {
+        self.combo = 0;
+    }
+
+    /// Points taken away for losing a life, which also ends the combo
+    pub fn life_lost(&mut self, config: &ScoringConfig) -> i32 {
+        self.combo = 0;
+        config.life_lost_penalty
+    }
+
+    /// The bonus for clearing the level as quickly as it was
+    pub fn level_cleared(&self, config: &ScoringConfig) -> i32 {
+        if config.fast_clear_time <= 0.0 {
+            return 0;
+        }
+
+        let remaining = (1.0 - self.level_time / config.fast_clear_time).max(0.0);
+        (config.fast_clear_bonus as f32 * remaining).round() as i32
+    }
+
+    /// Starts over for a new level
+    pub fn reset(&mut self) {
+        *self = Scoring::default();
+    }
+}
+
+/// A score the way every screen shows it, with the thousands separated
+pub fn format_score(score: i32) -> String {
+    let digits = score.abs().to_string();
+    let mut formatted = String::new();
+
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+
+    if score < 0 {
+        formatted.insert(0, '-');
+    }
+
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combo_raises_block_points_until_the_paddle() {
+        let config = ScoringConfig::default();
+        let mut scoring = Scoring::default();
+
+        assert_eq!(scoring.block_destroyed(100, &config), 100);
+        assert_eq!(scoring.block_destroyed(100, &config), 110);
+        assert_eq!(scoring.block_destroyed(200, &config), 240);
+
+        scoring.paddle_hit();
+        assert_eq!(scoring.block_destroyed(500, &config), 500);
+
+        for _ in 0..20 {
+            scoring.block_destroyed(100, &config);
+        }
+        assert_eq!(scoring.block_destroyed(100, &config), 200);
+    }
+
+    #[test]
+    fn fast_clear_bonus_runs_down() {
+        let config = ScoringConfig::default();
+        let mut scoring = Scoring::default();
+        assert_eq!(scoring.level_cleared(&config), 5000);
+
+        scoring.tick(config.fast_clear_time * 0.5);
+        assert_eq!(scoring.level_cleared(&config), 2500);
+
+        scoring.tick(config.fast_clear_time);
+        assert_eq!(scoring.level_cleared(&config), 0);
+    }
+
+    #[test]
+    fn scores_are_grouped_in_thousands() {
+        assert_eq!(format_score(0), "0");
+        assert_eq!(format_score(999), "999");
+        assert_eq!(format_score(1000), "1,000");
+        assert_eq!(format_score(1234567), "1,234,567");
+        assert_eq!(format_score(-4500), "-4,500");
+    }
+}
@@ -0,0 +1,48 @@
+use crate::config::BallConfig;
+
+/// How fast the balls are currently travelling and the progress towards speeding up. Every
+/// launched ball is kept at `current` speed.
+#[derive(Debug, Clone, Default)]
+pub struct BallSpeed {
+    pub base: f32,
+    pub current: f32,
+    pub paddle_hits: u32,
+    pub touched_top: bool,
+}
+
+impl BallSpeed {
+    pub fn new(base: f32) -> Self {
+        BallSpeed {
+            base,
+            current: base,
+            paddle_hits: 0,
+            touched_top: false,
+        }
+    }
+
+    /// Counts a paddle hit, speeding up every `paddle_hits_per_step` hits
+    pub fn paddle_hit(&mut self, config: &BallConfig) {
+        self.paddle_hits += 1;
+
+        if config.paddle_hits_per_step > 0 && self.paddle_hits % config.paddle_hits_per_step == 0 {
+            self.increase(config.speed_step, config.max_speed);
+        }
+    }
+
+    /// Speeds up the first time the top wall is touched in a life
+    pub fn top_wall_hit(&mut self, config: &BallConfig) {
+        if !self.touched_top {
+            self.touched_top = true;
+            self.increase(config.top_wall_step, config.max_speed);
+        }
+    }
+
+    /// Back to the level's base speed, used when a life is lost
+    pub fn reset(&mut self) {
+        *self = BallSpeed::new(self.base);
+    }
+
+    fn increase(&mut self, step: f32, max_speed: f32) {
+        self.current = (self.current + step).min(max_speed.max(self.base));
+    }
+}
@@ -0,0 +1,427 @@
+//! Checks levels against the config they are played with, so that a broken level is caught
+//! when it is loaded rather than when it is laid out or played.
+
+use crate::config::{BlockBehaviour, BreakoutConfig, PowerUpKind};
+use crate::levels::{Level, LevelsConfig, EMPTY_CELL};
+
+use std::{error::Error, fmt};
+
+/// Something wrong with a level, and where. Rows and columns count from 0 at the top left
+/// of the grid, like level indices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelProblem {
+    pub level: usize,
+    pub row: Option<usize>,
+    pub column: Option<usize>,
+    pub kind: ProblemKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProblemKind {
+    // A row with more blocks than fit across the arena
+    TooWide { width: usize, max_width: usize },
+    // A row that is not as wide as the first one
+    RaggedRow { width: usize, expected: usize },
+    // A grid character that is not a block type, or a legend name that is not one
+    UnknownBlock(char),
+    UnknownBlockName(String),
+    // Nothing on the level to break, so it would be cleared as soon as it started
+    Empty,
+    // A block walled in by indestructible blocks, which the ball can never reach
+    Unreachable,
+    // A block that drops a capsule the drop table has no entry for, which would have no
+    // sprite and no effect
+    MissingDrop(PowerUpKind),
+    // No levels at all, so there is nothing to start a game on. Reported as level 0.
+    NoLevels,
+}
+
+impl fmt::Display for LevelProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.kind == ProblemKind::NoLevels {
+            return write!(f, "there are no levels to play");
+        }
+
+        write!(f, "level {}", self.level)?;
+        if let Some(row) = self.row {
+            write!(f, " row {}", row)?;
+        }
+        if let Some(column) = self.column {
+            write!(f, " column {}", column)?;
+        }
+
+        match &self.kind {
+            ProblemKind::TooWide { width, max_width } => write!(
+                f,
+                ": {} blocks wide, the arena only fits {}",
+                width, max_width
+            ),
+            ProblemKind::RaggedRow { width, expected } => write!(
+                f,
+                ": {} blocks wide, the rows above are {}",
+                width, expected
+            ),
+            ProblemKind::UnknownBlock(cell) => write!(f, ": '{}' is not a block type", cell),
+            ProblemKind::UnknownBlockName(name) => {
+                write!(
+                    f,
+                    ": the legend names \"{}\", which is not a block type",
+                    name
+                )
+            }
+            ProblemKind::Empty => write!(f, ": there are no blocks to break"),
+            ProblemKind::Unreachable => write!(f, ": the ball can never reach this block"),
+            ProblemKind::MissingDrop(kind) => write!(
+                f,
+                ": the block drops {:?} capsules, which are not in the drop table",
+                kind
+            ),
+            ProblemKind::NoLevels => Ok(()),
+        }
+    }
+}
+
+/// Every problem with the levels, in level order
+#[derive(Debug)]
+pub struct InvalidLevels(pub Vec<LevelProblem>);
+
+impl fmt::Display for InvalidLevels {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} problems with the levels", self.0.len())?;
+        for problem in &self.0 {
+            write!(f, "\n  {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for InvalidLevels {}
+
+/// Fails with every problem found in the levels, for refusing to load them
+pub fn check_levels(config: &BreakoutConfig, levels: &LevelsConfig) -> Result<(), InvalidLevels> {
+    let problems = validate_levels(config, levels);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(InvalidLevels(problems))
+    }
+}
+
+pub fn validate_levels(config: &BreakoutConfig, levels: &LevelsConfig) -> Vec<LevelProblem> {
+    if levels.levels.is_empty() {
+        return vec![LevelProblem {
+            level: 0,
+            row: None,
+            column: None,
+            kind: ProblemKind::NoLevels,
+        }];
+    }
+
+    let max_width = (config.arena.width / config.block.width).floor() as usize;
+
+    levels
+        .levels
+        .iter()
+        .enumerate()
+        .flat_map(|(index, level)| validate_level(config, index, level, max_width))
+        .collect()
+}
+
+// What a grid cell turned out to be
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cell {
+    Open,
+    Breakable,
+    Wall,
+}
+
+fn validate_level(
+    config: &BreakoutConfig,
+    index: usize,
+    level: &Level,
+    max_width: usize,
+) -> Vec<LevelProblem> {
+    let mut problems = Vec::new();
+    let mut report = |row, column, kind| {
+        problems.push(LevelProblem {
+            level: index,
+            row,
+            column,
+            kind,
+        })
+    };
+
+    for name in level.legend.values() {
+        if config.block.by_name(name).is_none() {
+            report(None, None, ProblemKind::UnknownBlockName(name.clone()));
+        }
+    }
+
+    let expected = level.grid.first().map_or(0, |row| row.chars().count());
+    let mut cells = Vec::new();
+
+    for (row, line) in level.grid.iter().enumerate() {
+        let width = line.chars().count();
+        if width > max_width {
+            report(Some(row), None, ProblemKind::TooWide { width, max_width });
+        } else if width != expected {
+            report(Some(row), None, ProblemKind::RaggedRow { width, expected });
+        }
+
+        let mut row_cells = Vec::new();
+        for (column, cell) in line.chars().enumerate() {
+            if cell == EMPTY_CELL {
+                row_cells.push(Cell::Open);
+                continue;
+            }
+
+            row_cells.push(match level.block_type(cell, &config.block) {
+                Some(block_type) => {
+                    let block_type = &config.block.types[block_type];
+                    for behaviour in &block_type.behaviours {
+                        if let BlockBehaviour::Drops(kind) = *behaviour {
+                            if config.powerups.drop(kind).is_none() {
+                                report(Some(row), Some(column), ProblemKind::MissingDrop(kind));
+                            }
+                        }
+                    }
+
+                    if block_type.indestructible {
+                        Cell::Wall
+                    } else {
+                        Cell::Breakable
+                    }
+                }
+                None => {
+                    // A legend name that is not a block type was reported above
+                    if !level.legend.contains_key(&cell) {
+                        report(Some(row), Some(column), ProblemKind::UnknownBlock(cell));
+                    }
+                    Cell::Open
+                }
+            });
+        }
+        cells.push(row_cells);
+    }
+
+    if !cells.iter().flatten().any(|&cell| cell == Cell::Breakable) {
+        report(None, None, ProblemKind::Empty);
+        return problems;
+    }
+
+    // The ball can go anywhere across the arena, not only as far as the rows are drawn
+    let width = cells.iter().map(Vec::len).fold(max_width, usize::max);
+    for row_cells in cells.iter_mut() {
+        row_cells.resize(width, Cell::Open);
+    }
+
+    for (row, column) in unreachable(&cells) {
+        report(Some(row), Some(column), ProblemKind::Unreachable);
+    }
+
+    problems
+}
+
+// The breakable cells the ball cannot get to from below the grid. Breakable blocks open up
+// as they are broken, so only walls stop it, and it cannot squeeze between two walls that
+// only touch at a corner.
+fn unreachable(cells: &[Vec<Cell>]) -> Vec<(usize, usize)> {
+    let mut reached: Vec<Vec<bool>> = cells.iter().map(|row| vec![false; row.len()]).collect();
+    let mut open = Vec::new();
+
+    // The ball comes up from under the grid, into any cell on the bottom row that is not
+    // walled off. Every row is as wide as the arena.
+    if let Some(bottom) = cells.len().checked_sub(1) {
+        for column in 0..cells[bottom].len() {
+            open.push((bottom, column));
+        }
+    }
+
+    while let Some((row, column)) = open.pop() {
+        let cell = match cells.get(row).and_then(|cells| cells.get(column)) {
+            Some(&cell) => cell,
+            None => continue,
+        };
+        if cell == Cell::Wall || reached[row][column] {
+            continue;
+        }
+        reached[row][column] = true;
+
+        open.push((row + 1, column));
+        open.push((row, column + 1));
+        if row > 0 {
+            open.push((row - 1, column));
+        }
+        if column > 0 {
+            open.push((row, column - 1));
+        }
+    }
+
+    let mut unreachable = Vec::new();
+    for (row, cells) in cells.iter().enumerate() {
+        for (column, &cell) in cells.iter().enumerate() {
+            if cell == Cell::Breakable && !reached[row][column] {
+                unreachable.push((row, column));
+            }
+        }
+    }
+    unreachable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BlockType, PowerUpDrop};
+
+    fn config() -> BreakoutConfig {
+        let mut config = BreakoutConfig::default();
+        config.arena.width = 40.0;
+        config.block.types.push(BlockType {
+            name: "wall".to_string(),
+            symbol: '#',
+            hits: 1,
+            score: 0,
+            sprites: vec![6],
+            indestructible: true,
+            behaviours: Vec::new(),
+        });
+        config
+    }
+
+    fn problems(rows: &[&str]) -> Vec<(Option<usize>, Option<usize>, ProblemKind)> {
+        let level = Level {
+            grid: rows.iter().map(|row| row.to_string()).collect(),
+            ..Level::default()
+        };
+
+        validate_levels(&config(), &LevelsConfig::new(vec![level]))
+            .into_iter()
+            .map(|problem| (problem.row, problem.column, problem.kind))
+            .collect()
+    }
+
+    #[test]
+    fn good_levels_pass() {
+        assert!(problems(&["1.2.", "#33#"]).is_empty());
+
+        let (config, levels) = (
+            ron::de::from_str(include_str!("../../config/breakout.ron")).unwrap(),
+            LevelsConfig::from_ron(include_str!("../../config/levels.ron")).unwrap(),
+        );
+        assert_eq!(validate_levels(&config, &levels), Vec::new());
+    }
+
+    #[test]
+    fn problems_are_reported_where_they_are() {
+        assert_eq!(
+            problems(&["11111", "11"]),
+            vec![
+                (
+                    Some(0),
+                    None,
+                    ProblemKind::TooWide {
+                        width: 5,
+                        max_width: 4
+                    }
+                ),
+                (
+                    Some(1),
+                    None,
+                    ProblemKind::RaggedRow {
+                        width: 2,
+                        expected: 5
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            problems(&["1x.."]),
+            vec![(Some(0), Some(1), ProblemKind::UnknownBlock('x'))]
+        );
+        assert_eq!(
+            problems(&["....", "#..#"]),
+            vec![(None, None, ProblemKind::Empty)]
+        );
+        assert_eq!(
+            problems(&["#1#.", ".#.."]),
+            vec![(Some(0), Some(1), ProblemKind::Unreachable)]
+        );
+    }
+
+    #[test]
+    fn dropped_capsules_need_a_drop_entry() {
+        let mut config = config();
+        config.block.types.push(BlockType {
+            name: "bonus".to_string(),
+            symbol: '+',
+            hits: 1,
+            score: 100,
+            sprites: vec![2],
+            indestructible: false,
+            behaviours: vec![BlockBehaviour::Drops(PowerUpKind::ExtraLife)],
+        });
+        let levels = LevelsConfig::new(vec![Level {
+            grid: vec!["1+..".to_string()],
+            ..Level::default()
+        }]);
+
+        let problems = validate_levels(&config, &levels);
+        assert_eq!(
+            problems,
+            vec![LevelProblem {
+                level: 0,
+                row: Some(0),
+                column: Some(1),
+                kind: ProblemKind::MissingDrop(PowerUpKind::ExtraLife),
+            }]
+        );
+
+        config.powerups.drops.push(PowerUpDrop {
+            kind: PowerUpKind::ExtraLife,
+            chance: 0.0,
+            duration: 0.0,
+            sprite: 7,
+        });
+        assert!(validate_levels(&config, &levels).is_empty());
+    }
+
+    #[test]
+    fn campaigns_need_a_level() {
+        let problems = validate_levels(&config(), &LevelsConfig::new(Vec::new()));
+
+        assert_eq!(
+            problems,
+            vec![LevelProblem {
+                level: 0,
+                row: None,
+                column: None,
+                kind: ProblemKind::NoLevels,
+            }]
+        );
+        assert_eq!(problems[0].to_string(), "there are no levels to play");
+    }
+
+    #[test]
+    fn the_ball_gets_round_grids_narrower_than_the_arena() {
+        assert!(problems(&["#1", ".#"]).is_empty());
+        assert!(problems(&["#1#", "#"])
+            .iter()
+            .all(|(_, _, kind)| *kind != ProblemKind::Unreachable));
+
+        // Walls right across the arena still shut the ball out
+        assert_eq!(
+            problems(&["#1#", "####"]),
+            vec![
+                (
+                    Some(1),
+                    None,
+                    ProblemKind::RaggedRow {
+                        width: 4,
+                        expected: 3
+                    }
+                ),
+                (Some(0), Some(1), ProblemKind::Unreachable),
+            ]
+        );
+    }
+}
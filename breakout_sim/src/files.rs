@@ -0,0 +1,39 @@
+use breakout_core::config::BreakoutConfig;
+use breakout_core::levels::{LevelsConfig, LevelsError};
+use breakout_core::validation::check_levels;
+
+use serde::Deserialize;
+
+use std::{error::Error, fs, path::Path};
+
+pub fn load_ron<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T, Box<dyn Error>> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(ron::de::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?)
+}
+
+/// Loads the rules and levels the game would load from the same directories, and refuses
+/// levels that do not pass validation like the game does. The level files in `levels_dir`
+/// take the place of `levels.ron` when there are any.
+pub fn load_config(
+    config_dir: &Path,
+    levels_dir: &Path,
+) -> Result<(BreakoutConfig, LevelsConfig), Box<dyn Error>> {
+    let (config, levels) = load_unchecked_config(config_dir, levels_dir)?;
+    check_levels(&config, &levels)?;
+    Ok((config, levels))
+}
+
+/// Loads the rules and levels without validating the levels
+pub fn load_unchecked_config(
+    config_dir: &Path,
+    levels_dir: &Path,
+) -> Result<(BreakoutConfig, LevelsConfig), Box<dyn Error>> {
+    let config = load_ron(&config_dir.join("breakout.ron"))?;
+    let levels_path = config_dir.join("levels.ron");
+    let levels = LevelsConfig::load_from(levels_dir, &levels_path).map_err(|e| match e {
+        // Level file errors already say which file they are in
+        LevelsError::Text { .. } => e.to_string(),
+        _ => format!("{}: {}", levels_path.display(), e),
+    })?;
+    Ok((config, levels))
+}
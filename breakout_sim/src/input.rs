@@ -0,0 +1,127 @@
+use breakout_core::{Game, Input, ReplayPlayer};
+
+use serde::Deserialize;
+
+/// Something that plays the game, deciding the input for each tick
+pub trait InputSource {
+    fn next_input(&mut self, game: &Game, delta_seconds: f32) -> Input;
+}
+
+/// Plays by moving the paddle to where the next ball will come down, bouncing off the side
+/// walls on the way, and launching as soon as it can. Rising balls are expected to come
+/// straight back off the top wall. It catches the ball off center so that it heads towards
+/// the blocks that are left rather than straight up.
+#[derive(Default)]
+pub struct Autopilot;
+
+impl InputSource for Autopilot {
+    fn next_input(&mut self, game: &Game, delta_seconds: f32) -> Input {
+        let paddle = &game.paddle;
+        let paddle_top = paddle.position.y + paddle.height * 0.5;
+        let (arena_width, arena_height) = (game.config.arena.width, game.config.arena.height);
+
+        // Seconds until each ball gets back down to the paddle, and where it will be
+        let landing = game
+            .balls
+            .iter()
+            .filter(|ball| ball.has_launched && ball.velocity.y != 0.0)
+            .map(|ball| {
+                let catch_height = paddle_top + ball.radius;
+                let distance = if ball.velocity.y < 0.0 {
+                    ball.position.y - catch_height
+                } else {
+                    (arena_height - ball.radius) * 2.0 - ball.position.y - catch_height
+                };
+
+                let time = distance.max(0.0) / ball.velocity.y.abs();
+                let x = ball.position.x + ball.velocity.x * time;
+
+                // Fold the straight line path back into the arena
+                let span = (arena_width - ball.radius * 2.0).max(1.0);
+                let folded = (x - ball.radius).rem_euclid(span * 2.0);
+                let x = ball.radius
+                    + if folded > span {
+                        span * 2.0 - folded
+                    } else {
+                        folded
+                    };
+
+                (time, x)
+            })
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(_, x)| x);
+
+        // Catch the ball on the side of the paddle that sends it towards the remaining blocks
+        let target = match landing {
+            Some(x) => {
+                let blocks_x = game
+                    .blocks
+                    .iter()
+                    .map(|block| block.position.x)
+                    .sum::<f32>()
+                    / game.blocks.len().max(1) as f32;
+
+                if blocks_x < x {
+                    x + paddle.width * 0.3
+                } else {
+                    x - paddle.width * 0.3
+                }
+            }
+            None => paddle.position.x,
+        };
+
+        // Full speed until the paddle would overshoot in a single tick
+        let max_move = game.config.paddle.velocity * delta_seconds;
+        let movement = if max_move > 0.0 {
+            ((target - paddle.position.x) / max_move).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+
+        Input {
+            paddle: movement,
+            launch_ball: game.balls.iter().any(|ball| !ball.has_launched),
+            fire: true,
+            ..Input::default()
+        }
+    }
+}
+
+/// One stretch of a script, holding the same input for a number of ticks
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScriptStep {
+    pub ticks: u64,
+    pub input: Input,
+}
+
+/// Plays a fixed list of inputs, then leaves the controls alone once it runs out
+#[derive(Debug, Clone, Deserialize)]
+pub struct Script {
+    pub steps: Vec<ScriptStep>,
+    #[serde(skip)]
+    position: (usize, u64),
+}
+
+impl InputSource for Script {
+    fn next_input(&mut self, _game: &Game, _delta_seconds: f32) -> Input {
+        let (step, tick) = &mut self.position;
+
+        while let Some(current) = self.steps.get(*step) {
+            if *tick < current.ticks {
+                *tick += 1;
+                return current.input;
+            }
+
+            *step += 1;
+            *tick = 0;
+        }
+
+        Input::default()
+    }
+}
+
+impl InputSource for ReplayPlayer {
+    fn next_input(&mut self, _game: &Game, _delta_seconds: f32) -> Input {
+        ReplayPlayer::next_input(self).unwrap_or_default()
+    }
+}
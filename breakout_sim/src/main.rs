@@ -0,0 +1,252 @@
+//! Runs a game of breakout with no window and prints how it went as JSON, for balance
+//! testing on machines without a GPU. Like the game, it starts on `--level` and moves on
+//! through the later levels as each one is cleared.
+//!
+//! ```text
+//! breakout-sim [--config-dir DIR] [--levels-dir DIR] [--level N] [--seed N]
+//!              [--max-ticks N] [--script FILE] [--replay FILE] [--record FILE]
+//! breakout-sim --validate-levels [--config-dir DIR] [--levels-dir DIR]
+//! breakout-sim --export-levels DIR [--config-dir DIR] [--levels-dir DIR]
+//! ```
+//!
+//! Levels come from the level files in `--levels-dir` (`levels` by default) when there are
+//! any, and from `levels.ron` in the config directory otherwise, the same as the game.
+//!
+//! Without a script the paddle is driven by a simple autopilot that follows the ball. A
+//! script is a RON file of inputs, each held for a number of ticks:
+//!
+//! ```text
+//! (
+//!     steps: [
+//!         (ticks: 1, input: (paddle: 0.0, launch_ball: true, fire: false)),
+//!         (ticks: 120, input: (paddle: -1.0, launch_ball: false, fire: false)),
+//!     ],
+//! )
+//! ```
+//!
+//! `--replay` plays a replay recorded by the game or by `--record` instead, with the level
+//! and seed it was recorded with, and fails if the config has changed since.
+//!
+//! `--validate-levels` plays nothing, it lists every problem with the levels instead and
+//! exits with 1 if there are any. `--export-levels` writes the levels out as level files
+//! in a directory, which can then be used as the levels directory.
+
+mod files;
+mod input;
+
+use crate::files::{load_config, load_ron, load_unchecked_config};
+use crate::input::{Autopilot, InputSource, Script};
+
+use breakout_core::level_files::export_dir;
+use breakout_core::replay::{config_hash, Replay, ReplayPlayer};
+use breakout_core::validation::validate_levels;
+use breakout_core::Game;
+
+use serde::Serialize;
+
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    process,
+};
+
+const USAGE: &str = "usage: breakout-sim [--config-dir DIR] [--levels-dir DIR] [--level N] \
+                     [--seed N] [--max-ticks N] [--script FILE] [--replay FILE] \
+                     [--record FILE]\n       \
+                     breakout-sim --validate-levels [--config-dir DIR] [--levels-dir DIR]\n       \
+                     breakout-sim --export-levels DIR [--config-dir DIR] [--levels-dir DIR]";
+
+/// What the simulation printed at the end of a run
+#[derive(Debug, Serialize)]
+struct SimResult {
+    // The level the run ended on
+    level: usize,
+    seed: u64,
+    score: i32,
+    lives: i32,
+    blocks_remaining: i32,
+    ticks: u64,
+}
+
+struct Options {
+    config_dir: PathBuf,
+    levels_dir: PathBuf,
+    level: usize,
+    seed: u64,
+    max_ticks: u64,
+    script: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    record: Option<PathBuf>,
+    validate_levels: bool,
+    export_levels: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            config_dir: PathBuf::from("config"),
+            levels_dir: PathBuf::from("levels"),
+            level: 0,
+            seed: 1,
+            // Ten minutes of play at the default tick rate
+            max_ticks: 120 * 60 * 10,
+            script: None,
+            replay: None,
+            record: None,
+            validate_levels: false,
+            export_levels: None,
+        }
+    }
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+        match arg.as_str() {
+            "--config-dir" => options.config_dir = PathBuf::from(value()?),
+            "--levels-dir" => options.levels_dir = PathBuf::from(value()?),
+            "--level" => options.level = parse_number(&value()?)?,
+            "--seed" => options.seed = parse_number(&value()?)?,
+            "--max-ticks" => options.max_ticks = parse_number(&value()?)?,
+            "--script" => options.script = Some(PathBuf::from(value()?)),
+            "--replay" => options.replay = Some(PathBuf::from(value()?)),
+            "--record" => options.record = Some(PathBuf::from(value()?)),
+            "--validate-levels" => options.validate_levels = true,
+            "--export-levels" => options.export_levels = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} is not a valid number", value))
+}
+
+fn run(options: &Options) -> Result<SimResult, Box<dyn Error>> {
+    let (config, levels) = load_config(&options.config_dir, &options.levels_dir)?;
+
+    let delta_seconds = config.simulation.tick_seconds();
+    let hash = config_hash(&config, &levels);
+
+    let (mut game, mut source, seed, max_ticks): (_, Box<dyn InputSource>, _, _) = match &options
+        .replay
+    {
+        Some(path) => {
+            let replay = Replay::load(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let game = replay.start(config, &levels)?;
+            let (seed, ticks) = (replay.seed, replay.ticks());
+            (game, Box::new(ReplayPlayer::new(replay)), seed, ticks)
+        }
+        None => {
+            if options.level >= levels.levels.len() {
+                return Err(format!("there is no level {}", options.level).into());
+            }
+
+            let source: Box<dyn InputSource> = match &options.script {
+                Some(path) => Box::new(load_ron::<Script>(path)?),
+                None => Box::new(Autopilot),
+            };
+
+            let game = Game::new(config, &levels, options.level, options.seed);
+            (game, source, options.seed, options.max_ticks)
+        }
+    };
+
+    let mut recording = Replay::new(hash, game.level_index, seed);
+    let mut ticks = 0;
+
+    while ticks < max_ticks && game.continue_campaign(&levels) {
+        let input = source.next_input(&game, delta_seconds);
+        recording.record(input);
+        game.step(&input, delta_seconds);
+        ticks += 1;
+    }
+
+    if let Some(path) = &options.record {
+        recording
+            .save(path)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    Ok(SimResult {
+        level: game.level_index,
+        seed,
+        score: game.score,
+        lives: game.lives,
+        blocks_remaining: game.blocks_remaining(),
+        ticks,
+    })
+}
+
+// Prints every problem with the levels, returning whether there were none
+fn validate(options: &Options) -> Result<bool, Box<dyn Error>> {
+    let (config, levels) = load_unchecked_config(&options.config_dir, &options.levels_dir)?;
+    let problems = validate_levels(&config, &levels);
+
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    println!(
+        "{} levels checked, {} problems",
+        levels.levels.len(),
+        problems.len()
+    );
+
+    Ok(problems.is_empty())
+}
+
+// Writes the levels out as level files, listing the files written
+fn export(options: &Options, dir: &Path) -> Result<(), Box<dyn Error>> {
+    let (_, levels) = load_unchecked_config(&options.config_dir, &options.levels_dir)?;
+
+    for path in export_dir(&levels, dir).map_err(|e| format!("{}: {}", dir.display(), e))? {
+        println!("{}", path.display());
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|e| {
+        eprintln!("{}\n{}", e, USAGE);
+        process::exit(2);
+    });
+
+    if let Some(dir) = &options.export_levels {
+        if let Err(e) = export(&options, dir) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if options.validate_levels {
+        match validate(&options) {
+            Ok(true) => return,
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+    }
+
+    match run(&options) {
+        Ok(result) => println!(
+            "{}",
+            serde_json::to_string(&result).expect("Failed to serialize result")
+        ),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
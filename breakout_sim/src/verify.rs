@@ -0,0 +1,136 @@
+//! Checks a claimed score by playing its replay again with no window.
+//!
+//! ```text
+//! breakout-verify REPLAY --score N [--outcome cleared|out-of-lives|playing]
+//!                 [--config-dir DIR] [--levels-dir DIR]
+//! ```
+//!
+//! The replay has to have been recorded against the same config and levels as the ones
+//! loaded from the config and levels directories, otherwise it is rejected. The result is
+//! printed as JSON and the exit code is 0 only when the replay plays out to exactly the
+//! claimed score and outcome.
+
+mod files;
+
+use crate::files::load_config;
+
+use breakout_core::{Outcome, Replay};
+
+use serde::Serialize;
+
+use std::{error::Error, path::PathBuf, process};
+
+const USAGE: &str = "usage: breakout-verify REPLAY --score N \
+                     [--outcome cleared|out-of-lives|playing] [--config-dir DIR] \
+                     [--levels-dir DIR]";
+
+/// What the replay actually played out to
+#[derive(Debug, Serialize)]
+struct Verdict {
+    verified: bool,
+    level: usize,
+    seed: u64,
+    score: i32,
+    outcome: Outcome,
+    ticks: u64,
+}
+
+struct Options {
+    config_dir: PathBuf,
+    levels_dir: PathBuf,
+    replay: PathBuf,
+    score: i32,
+    outcome: Option<Outcome>,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut config_dir = PathBuf::from("config");
+    let mut levels_dir = PathBuf::from("levels");
+    let mut replay = None;
+    let mut score = None;
+    let mut outcome = None;
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+        match arg.as_str() {
+            "--config-dir" => config_dir = PathBuf::from(value()?),
+            "--levels-dir" => levels_dir = PathBuf::from(value()?),
+            "--score" => {
+                let value = value()?;
+                score = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("{} is not a valid score", value))?,
+                );
+            }
+            "--outcome" => outcome = Some(parse_outcome(&value()?)?),
+            _ if !arg.starts_with("--") && replay.is_none() => replay = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+
+    Ok(Options {
+        config_dir,
+        levels_dir,
+        replay: replay.ok_or("no replay given")?,
+        score: score.ok_or("no claimed score given")?,
+        outcome,
+    })
+}
+
+fn parse_outcome(value: &str) -> Result<Outcome, String> {
+    match value {
+        "cleared" => Ok(Outcome::Cleared),
+        "out-of-lives" => Ok(Outcome::OutOfLives),
+        "playing" => Ok(Outcome::Playing),
+        _ => Err(format!("{} is not an outcome", value)),
+    }
+}
+
+fn verify(options: &Options) -> Result<Verdict, Box<dyn Error>> {
+    let (config, levels) = load_config(&options.config_dir, &options.levels_dir)?;
+    let replay = Replay::load(&options.replay)
+        .map_err(|e| format!("{}: {}", options.replay.display(), e))?;
+
+    let game = replay
+        .play(config, &levels)
+        .map_err(|e| format!("rejected: {}", e))?;
+
+    let outcome = game.outcome();
+
+    Ok(Verdict {
+        verified: game.score == options.score
+            && options.outcome.map_or(true, |claimed| claimed == outcome),
+        level: replay.level,
+        seed: replay.seed,
+        score: game.score,
+        outcome,
+        ticks: replay.ticks(),
+    })
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|e| {
+        eprintln!("{}\n{}", e, USAGE);
+        process::exit(2);
+    });
+
+    match verify(&options) {
+        Ok(verdict) => {
+            println!(
+                "{}",
+                serde_json::to_string(&verdict).expect("Failed to serialize verdict")
+            );
+
+            if !verdict.verified {
+                process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
@@ -1,30 +1,54 @@
-use crate::systems::{BlockStateSystem, BlockSystem, BounceSystem, MoveBallSystem, PaddleSystem};
+use crate::systems::{
+    BlockStateSystem, BlockSystem, CollisionSystem, LaserSystem, MoveBallSystem, PaddleSystem,
+    PowerUpSystem, StepSystemDesc, TickBeginSystem, TickEndSystem,
+};
 
 use amethyst::{
-    core::bundle::SystemBundle,
+    core::{bundle::SystemBundle, SystemDesc},
     ecs::prelude::{DispatcherBuilder, World},
     error::Error,
 };
 
+/// The gameplay systems. These are not added to the main dispatcher, the `Breakout` state
+/// runs them once per fixed simulation tick.
 pub struct BreakoutBundle;
 
 impl<'a, 'b> SystemBundle<'a, 'b> for BreakoutBundle {
     fn build(
         self,
-        _world: &mut World,
+        world: &mut World,
         builder: &mut DispatcherBuilder<'a, 'b>,
     ) -> Result<(), Error> {
-        builder.add(PaddleSystem, "paddle_system", &["input_system"]);
-        builder.add(MoveBallSystem, "ball_system", &["input_system"]);
+        builder.add(TickBeginSystem, "tick_begin_system", &[]);
+
+        // The game rules run here, everything below brings the entities in line with them
+        builder.add(
+            StepSystemDesc::default().build(world),
+            "step_system",
+            &["tick_begin_system"],
+        );
 
-        builder.add(BlockSystem, "block_system", &["ball_system"]);
+        // Contacts go out on the Collision event channel for anything that wants to react
+        builder.add(CollisionSystem, "collision_system", &["step_system"]);
 
+        builder.add(PaddleSystem, "paddle_system", &["step_system"]);
+        builder.add(MoveBallSystem, "ball_system", &["step_system"]);
+        builder.add(BlockSystem, "block_system", &["step_system"]);
         builder.add(BlockStateSystem, "block_state_system", &["block_system"]);
+        builder.add(PowerUpSystem, "power_up_system", &["step_system"]);
+        builder.add(LaserSystem, "laser_system", &["step_system"]);
 
         builder.add(
-            BounceSystem,
-            "collision_system",
-            &["paddle_system", "ball_system"],
+            TickEndSystem,
+            "tick_end_system",
+            &[
+                "paddle_system",
+                "ball_system",
+                "block_state_system",
+                "power_up_system",
+                "laser_system",
+                "collision_system",
+            ],
         );
 
         Ok(())
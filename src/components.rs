@@ -1,13 +1,15 @@
+use breakout_core::ObjectId;
+
 use ::amethyst::{
     core::math::Vector2,
     ecs::{Component, DenseVecStorage, NullStorage},
 };
 
+// The gameplay state of everything below lives in the `Game` resource, these components
+// only tie an entity to the object it draws
+
 pub struct Ball {
-    pub velocity: Vector2<f32>,
-    pub radius: f32,
-    pub has_launched: bool,
-    pub last_position: Vector2<f32>,
+    pub id: ObjectId,
 }
 
 impl Component for Ball {
@@ -15,28 +17,58 @@ impl Component for Ball {
 }
 
 #[derive(Default)]
-pub struct Paddle {
-    pub velocity: f32,
-    pub width: f32,
-    pub height: f32,
-}
+pub struct Paddle;
 
 impl Component for Paddle {
-    type Storage = DenseVecStorage<Self>;
+    type Storage = NullStorage<Self>;
 }
 
 pub struct Block {
-    pub width: f32,
-    pub height: f32,
-    pub max_hits: i32,
-    pub cur_hits: i32,
-    pub cur_damage_state: usize,
+    pub id: ObjectId,
 }
 
 impl Component for Block {
     type Storage = DenseVecStorage<Self>;
 }
 
+/// A falling power up that takes effect when the paddle catches it
+pub struct Capsule {
+    pub id: ObjectId,
+}
+
+impl Component for Capsule {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// A laser shot fired by the paddle
+pub struct Projectile {
+    pub id: ObjectId,
+}
+
+impl Component for Projectile {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Simulation positions of an entity at the previous and latest fixed tick. The rendered
+/// transform is blended between the two.
+pub struct Interpolated {
+    pub previous: Vector2<f32>,
+    pub current: Vector2<f32>,
+}
+
+impl Interpolated {
+    pub fn new(position: Vector2<f32>) -> Self {
+        Interpolated {
+            previous: position,
+            current: position,
+        }
+    }
+}
+
+impl Component for Interpolated {
+    type Storage = DenseVecStorage<Self>;
+}
+
 #[derive(Default)]
 pub struct BreakoutRemovalTag;
 
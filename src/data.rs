@@ -1,15 +1,136 @@
+pub use breakout_core::speed::BallSpeed;
+pub use breakout_core::GameMode;
+
+use breakout_core::{Game, Outcome, Replay, ReplayPlayer};
+
+use amethyst::{assets::Handle, renderer::SpriteSheet};
+
 #[derive(Default)]
 pub struct PauseState {
     pub paused: bool,
 }
 
-#[derive(Default)]
-pub struct ScoreBoard {
-    pub current_score: i32,
+/// How the player did on one level of a session
+#[derive(Debug, Clone, Default)]
+pub struct LevelStats {
+    pub level: usize,
+    pub score: i32,
+    pub lives_lost: i32,
+    pub ticks: u64,
 }
 
-#[derive(Default)]
-pub struct LevelInfo {
-    pub num_blocks_remaining: i32,
-    pub num_lives_remaining: i32,
+/// One run, from leaving the main menu to the results screen. The main menu starts it, the
+/// game keeps it up to date as it plays and the pause and results screens show it.
+#[derive(Debug, Clone)]
+pub struct GameSession {
+    pub mode: GameMode,
+    pub starting_level: usize,
+    pub current_level: usize,
+    pub lives: i32,
+    pub score: i32,
+    // One entry for each level played so far, in order
+    pub levels: Vec<LevelStats>,
+    pub outcome: Outcome,
+}
+
+impl GameSession {
+    pub fn new(mode: GameMode, starting_level: usize) -> Self {
+        GameSession {
+            mode,
+            starting_level,
+            current_level: starting_level,
+            lives: 0,
+            score: 0,
+            levels: Vec::new(),
+            outcome: Outcome::Playing,
+        }
+    }
+
+    /// Ticks played over the whole session
+    pub fn ticks(&self) -> u64 {
+        self.levels.iter().map(|stats| stats.ticks).sum()
+    }
+
+    /// Catches up with the game after it ran for `ticks` more ticks
+    pub fn update(&mut self, game: &Game, ticks: u64) {
+        if self.levels.last().map(|stats| stats.level) != Some(game.level_index) {
+            self.levels.push(LevelStats {
+                level: game.level_index,
+                ..LevelStats::default()
+            });
+        }
+
+        if let Some(stats) = self.levels.last_mut() {
+            stats.score += game.score - self.score;
+            stats.lives_lost += (self.lives - game.lives).max(0);
+            stats.ticks += ticks;
+        }
+
+        self.current_level = game.level_index;
+        self.lives = game.lives;
+        self.score = game.score;
+        self.outcome = game.outcome();
+    }
+}
+
+/// Accumulates frame time and hands it out as fixed simulation ticks, so that the gameplay
+/// systems always integrate with the same step no matter how long a frame took.
+pub struct FixedTimestep {
+    pub tick_seconds: f32,
+    pub max_substeps: u32,
+    pub accumulator: f32,
+    pub tick: u64,
+}
+
+impl FixedTimestep {
+    pub fn new(tick_rate: u32, max_substeps: u32) -> Self {
+        FixedTimestep {
+            tick_seconds: 1.0 / tick_rate.max(1) as f32,
+            max_substeps: max_substeps.max(1),
+            accumulator: 0.0,
+            tick: 0,
+        }
+    }
+
+    /// Adds a frame's worth of time and returns how many ticks should be simulated
+    pub fn advance(&mut self, delta_seconds: f32) -> u32 {
+        self.accumulator += delta_seconds;
+
+        let mut ticks = 0;
+        while self.accumulator >= self.tick_seconds && ticks < self.max_substeps {
+            self.accumulator -= self.tick_seconds;
+            ticks += 1;
+        }
+
+        // After a long hitch we cannot catch up, drop the backlog rather than spiral
+        if self.accumulator >= self.tick_seconds {
+            self.accumulator %= self.tick_seconds;
+        }
+
+        self.tick += u64::from(ticks);
+        ticks
+    }
+
+    /// How far the simulation is between the last tick and the next one, for rendering
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.tick_seconds).min(1.0)
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        FixedTimestep::new(120, 8)
+    }
+}
+
+/// The sprite sheet gameplay entities are drawn from, for systems that spawn them mid-play
+pub struct BreakoutSprites {
+    pub sprite_sheet: Handle<SpriteSheet>,
+}
+
+/// Where the input of the current run comes from. A live run records every tick so it can
+/// be saved as a replay when it ends.
+pub enum ReplayState {
+    Recording(Replay),
+    Playing(ReplayPlayer),
 }
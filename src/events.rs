@@ -0,0 +1,24 @@
+use breakout_core::{CollisionSurface, ObjectId};
+
+use amethyst::core::math::Vector2;
+
+/// Published every tick for each surface a ball or laser shot touched during the step, for
+/// effects like sound and particles. `ball` is the ball or shot that moved into the
+/// surface, `normal` points away from the surface and `point` is where its center was at
+/// the moment of contact.
+#[derive(Debug, Clone)]
+pub struct Collision {
+    pub ball: ObjectId,
+    pub surface: CollisionSurface,
+    pub normal: Vector2<f32>,
+    pub point: Vector2<f32>,
+}
+
+/// Write this event to put another ball into play, e.g. from a power up. The new ball is
+/// already launched. It goes into the game with the input of the next tick, one ball per
+/// tick, so that replays record it like the rest of the input.
+#[derive(Debug, Clone)]
+pub struct SpawnBall {
+    pub position: Vector2<f32>,
+    pub velocity: Vector2<f32>,
+}
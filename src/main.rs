@@ -2,6 +2,7 @@ mod bundle;
 mod components;
 mod config;
 mod data;
+mod events;
 mod states;
 mod systems;
 mod util;
@@ -19,9 +20,11 @@ use amethyst::{
     utils::application_root_dir,
 };
 
-use crate::bundle::BreakoutBundle;
-use crate::config::{BreakoutConfig, LevelsData};
+use crate::config::{BreakoutConfig, LevelsConfig};
 use crate::states::MainMenu;
+use crate::systems::InterpolationSystem;
+
+use breakout_core::validation::{check_levels, validate_levels};
 
 use std::time::Duration;
 
@@ -40,18 +43,37 @@ fn main() -> amethyst::Result<()> {
     let breakout_config = BreakoutConfig::load(&breakout_config_path)?;
 
     let breakout_levels_path = config_dir.join("levels.ron");
-    let breakout_levels = LevelsData::load(&breakout_levels_path)?;
+    // Level files in the levels directory take the place of levels.ron when there are any
+    let breakout_levels = LevelsConfig::load_from(&app_root.join("levels"), &breakout_levels_path)?;
+
+    // `--validate-levels` only reports on the levels, without starting the game
+    if std::env::args().any(|arg| arg == "--validate-levels") {
+        let problems = validate_levels(&breakout_config, &breakout_levels);
+        for problem in &problems {
+            println!("{}", problem);
+        }
+        println!(
+            "{} levels checked, {} problems",
+            breakout_levels.levels.len(),
+            problems.len()
+        );
+        std::process::exit(if problems.is_empty() { 0 } else { 1 });
+    }
+
+    check_levels(&breakout_config, &breakout_levels)?;
 
     let binding_path = config_dir.join("bindings.ron");
     let input_bundle =
         InputBundle::<StringBindings>::new().with_bindings_from_file(binding_path)?;
 
     // Game Data setup
+    // The gameplay systems run on a fixed tick inside the Breakout state, only the render
+    // interpolation runs every frame. It has to come before the transform system.
     let game_data = GameDataBuilder::default()
+        .with(InterpolationSystem, "interpolation_system", &[])
         .with_bundle(TransformBundle::new())?
         .with_bundle(input_bundle)?
         .with_bundle(UiBundle::<StringBindings>::new())?
-        .with_bundle(BreakoutBundle)?
         .with_bundle(
             RenderingBundle::<DefaultBackend>::new()
                 // The RenderToWindow plugin provides all the scaffolding for opening a window and drawing on it
@@ -70,11 +92,15 @@ fn main() -> amethyst::Result<()> {
             FrameRateLimitStrategy::SleepAndYield(Duration::from_millis(2)),
             144,
         )
+        .with_resource(breakout_config.clone())
         .with_resource(breakout_config.arena)
         .with_resource(breakout_config.ball)
         .with_resource(breakout_config.block)
         .with_resource(breakout_config.paddle)
-        .with_resource(breakout_levels.levels)
+        .with_resource(breakout_config.powerups)
+        .with_resource(breakout_config.laser)
+        .with_resource(breakout_config.simulation)
+        .with_resource(breakout_levels)
         .build(game_data)?;
 
     game.run();
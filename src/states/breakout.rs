@@ -1,13 +1,18 @@
+use crate::bundle::BreakoutBundle;
 use crate::components::*;
-use crate::config::{ArenaConfig, BallConfig, BlockConfig, LevelsConfig, PaddleConfig};
-use crate::data::{LevelInfo, PauseState, ScoreBoard};
-use crate::states::{PauseMenu, Results};
-use crate::util::delete_hierarchy;
+use crate::config::{ArenaConfig, BreakoutConfig, LevelsConfig, SimulationConfig};
+use crate::data::{BreakoutSprites, FixedTimestep, GameSession, PauseState, ReplayState};
+use crate::states::{LevelComplete, PauseMenu, Results};
+use crate::util::{delete_hierarchy, replay_path};
+
+use breakout_core::{
+    replay::config_hash, scoring::format_score, Game, GameEvent, Replay, ReplayPlayer,
+};
 
 use amethyst::{
     assets::{AssetStorage, Handle, Loader},
-    core::{math::Vector2, transform::Transform},
-    ecs::prelude::{Entity, Join},
+    core::{transform::Transform, ArcThreadPool, SystemBundle, Time},
+    ecs::prelude::{Dispatcher, DispatcherBuilder, Entity, Join},
     ecs::world::EntitiesRes,
     input::{is_close_requested, is_key_down, VirtualKeyCode},
     prelude::*,
@@ -15,28 +20,57 @@ use amethyst::{
     ui::{UiCreator, UiFinder, UiText},
 };
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
 const TEXT_SCORE: &str = "score_text";
 const TEXT_LIVES: &str = "lives_text";
 
+// How long the lives counter flashes after an extra life, and how fast
+const LIFE_AWARDED_FLASH: f32 = 1.5;
+const LIFE_AWARDED_BLINK: f32 = 0.15;
+const LIVES_COLOR: [f32; 4] = [1.0, 0.65, 0., 1.0];
+const LIVES_FLASH_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+/// Plays the `GameSession` the main menu set up, from its starting level on
 #[derive(Default)]
 pub struct Breakout {
+    dispatcher: Option<Dispatcher<'static, 'static>>,
     sprite_sheet_handle: Option<Handle<SpriteSheet>>,
-    level_index: usize,
+    // The replay to play back instead of taking the player's input
+    replay: Option<Replay>,
     ui_root: Option<Entity>,
     score_text: Option<Entity>,
     lives_text: Option<Entity>,
+    // Time left on the flash of the lives counter
+    life_awarded_flash: f32,
 }
 
 impl Breakout {
-    pub fn new(index: usize) -> Self {
+    /// Plays a recorded run back. The replay has to match the loaded config.
+    pub fn replay(replay: Replay) -> Self {
         Breakout {
-            sprite_sheet_handle: None,
-            level_index: index,
-            ui_root: None,
-            score_text: None,
-            lives_text: None,
+            replay: Some(replay),
+            ..Breakout::default()
         }
     }
+
+    // Swaps the entities of the level just cleared for the ones of the level the game moved
+    // on to
+    fn start_next_level(&mut self, world: &mut World) {
+        delete_gameplay_entities(world);
+
+        let game = world.remove::<Game>().expect("Game is not set up");
+        let sprite_sheet_handle = self.sprite_sheet_handle.clone().unwrap();
+
+        world.insert(game.ball_speed.clone());
+
+        initialise_level(world, sprite_sheet_handle.clone(), &game);
+        initialise_ball(world, sprite_sheet_handle.clone(), &game);
+        initialise_paddle(world, sprite_sheet_handle, &game);
+        initialise_camera(world);
+
+        world.insert(game);
+    }
 }
 
 impl SimpleState for Breakout {
@@ -48,44 +82,123 @@ impl SimpleState for Breakout {
 
         world.register::<BreakoutRemovalTag>();
 
+        // The gameplay systems get their own dispatcher so they can run on a fixed tick
+        let mut dispatcher_builder =
+            DispatcherBuilder::new().with_pool((*world.read_resource::<ArcThreadPool>()).clone());
+        BreakoutBundle
+            .build(world, &mut dispatcher_builder)
+            .expect("Failed to build gameplay systems");
+
+        let mut dispatcher = dispatcher_builder.build();
+        dispatcher.setup(world);
+        self.dispatcher = Some(dispatcher);
+
+        let fixed_timestep = {
+            let config = world.read_resource::<SimulationConfig>();
+            FixedTimestep::new(config.tick_rate, config.max_substeps)
+        };
+        world.insert(fixed_timestep);
+
+        let starting_level = world.read_resource::<GameSession>().starting_level;
+
+        let (game, replay_state) = {
+            let config = world.read_resource::<BreakoutConfig>();
+            let levels = world.read_resource::<LevelsConfig>();
+
+            match self.replay.take() {
+                Some(replay) => {
+                    let game = replay
+                        .start(config.clone(), &levels)
+                        .expect("Replay does not match the config");
+                    (game, ReplayState::Playing(ReplayPlayer::new(replay)))
+                }
+                None => {
+                    // Gameplay randomness, seeded fresh for every run
+                    let seed = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|elapsed| elapsed.as_nanos() as u64)
+                        .unwrap_or(0);
+
+                    let replay = Replay::new(config_hash(&config, &levels), starting_level, seed);
+                    let game = Game::new(config.clone(), &levels, starting_level, seed);
+                    (game, ReplayState::Recording(replay))
+                }
+            }
+        };
+
         // Load the spritesheet necessary to render the graphics.
         // `spritesheet` is the layout of the sprites on the image;
         // `texture` is the pixel data.
         self.sprite_sheet_handle.replace(load_sprite_sheet(world));
+        world.insert(BreakoutSprites {
+            sprite_sheet: self.sprite_sheet_handle.clone().unwrap(),
+        });
 
         // Set initial pause bool
         let pause_state = PauseState { paused: false };
 
-        // Set initial score
-        let score_board = ScoreBoard { current_score: 0 };
-
-        // Init block count
-        let mut level_info = LevelInfo {
-            num_blocks_remaining: 0,
-            num_lives_remaining: 3,
-        };
-
+        world.write_resource::<GameSession>().update(&game, 0);
         world.insert(pause_state);
-        world.insert(score_board);
+        world.insert(game.ball_speed.clone());
+        world.insert(replay_state);
 
-        initialise_level(
-            world,
-            self.sprite_sheet_handle.clone().unwrap(),
-            self.level_index,
-            &mut level_info.num_blocks_remaining,
-        );
-
-        world.insert(level_info);
-
-        initialise_ball(world, self.sprite_sheet_handle.clone().unwrap());
-        initialise_paddle(world, self.sprite_sheet_handle.clone().unwrap());
+        initialise_level(world, self.sprite_sheet_handle.clone().unwrap(), &game);
+        initialise_ball(world, self.sprite_sheet_handle.clone().unwrap(), &game);
+        initialise_paddle(world, self.sprite_sheet_handle.clone().unwrap(), &game);
         initialise_camera(world);
+
+        world.insert(game);
     }
 
     fn update(&mut self, state_data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
-        // only search for buttons if they have not been found yet
         let StateData { world, .. } = state_data;
 
+        // Step the simulation for however many fixed ticks fit in this frame
+        let delta_seconds = world.read_resource::<Time>().delta_seconds();
+        let ticks = world
+            .write_resource::<FixedTimestep>()
+            .advance(delta_seconds);
+
+        let mut ticks_run = 0;
+        if let Some(dispatcher) = self.dispatcher.as_mut() {
+            for _ in 0..ticks {
+                // Nothing more happens on a level once it is over, it is dealt with below
+                if world.read_resource::<Game>().is_over() {
+                    break;
+                }
+
+                dispatcher.dispatch(world);
+                world.maintain();
+                ticks_run += 1;
+
+                // Events only last a tick, so catch the extra lives as they come
+                let game = world.read_resource::<Game>();
+                if game.events.contains(&GameEvent::LifeAwarded) {
+                    self.life_awarded_flash = LIFE_AWARDED_FLASH;
+                }
+            }
+        }
+
+        world
+            .write_resource::<GameSession>()
+            .update(&world.read_resource::<Game>(), ticks_run);
+
+        // Once a level is cleared the campaign moves on to the next one, with a short break
+        // in between. The run ends after the last level or with the last life.
+        let (cleared_level, next_level, campaign_continues) = {
+            let levels = world.read_resource::<LevelsConfig>();
+            let mut game = world.write_resource::<Game>();
+            let cleared_level = game.level_index;
+            let campaign_continues = game.continue_campaign(&levels);
+            (cleared_level, game.level_index, campaign_continues)
+        };
+
+        if next_level != cleared_level {
+            self.start_next_level(world);
+            return Trans::Push(Box::new(LevelComplete::new(cleared_level, next_level)));
+        }
+
+        // only search for buttons if they have not been found yet
         if self.lives_text.is_none() || self.score_text.is_none() {
             world.exec(|ui_finder: UiFinder<'_>| {
                 self.lives_text = ui_finder.find(TEXT_LIVES);
@@ -93,7 +206,7 @@ impl SimpleState for Breakout {
             });
         }
 
-        let level_info = &world.read_resource::<LevelInfo>();
+        let session = &world.read_resource::<GameSession>();
         {
             let mut ui_text = world.write_storage::<UiText>();
             {
@@ -103,16 +216,20 @@ impl SimpleState for Breakout {
                 {
                     let lives_string = "LIVES: ";
 
-                    text.text = format!(
-                        "{}{}",
-                        lives_string,
-                        level_info.num_lives_remaining.to_string()
-                    );
+                    text.text = format!("{}{}", lives_string, session.lives.to_string());
+
+                    // Blink the counter for a moment after an extra life
+                    self.life_awarded_flash = (self.life_awarded_flash - delta_seconds).max(0.0);
+                    let blink = (self.life_awarded_flash / LIFE_AWARDED_BLINK) as u32 % 2 == 1;
+                    text.color = if blink {
+                        LIVES_FLASH_COLOR
+                    } else {
+                        LIVES_COLOR
+                    };
                 }
             }
         }
 
-        let score_board = &world.read_resource::<ScoreBoard>();
         {
             let mut ui_text = world.write_storage::<UiText>();
             {
@@ -122,16 +239,18 @@ impl SimpleState for Breakout {
                 {
                     let score_string = "SCORE: ";
 
-                    text.text = format!(
-                        "{}{}",
-                        score_string,
-                        (score_board.current_score * 100).to_string()
-                    );
+                    text.text = format!("{}{}", score_string, format_score(session.score));
                 }
             }
         }
 
-        if level_info.num_lives_remaining == 0 || level_info.num_blocks_remaining == 0 {
+        // A replay that ends before the game does was quit part way through
+        let replay_over = match &*world.read_resource::<ReplayState>() {
+            ReplayState::Playing(player) => player.is_finished(),
+            ReplayState::Recording(_) => false,
+        };
+
+        if !campaign_continues || replay_over {
             return Trans::Switch(Box::new(Results::default()));
         }
 
@@ -181,19 +300,34 @@ impl SimpleState for Breakout {
         self.ui_root = None;
         self.score_text = None;
         self.lives_text = None;
+        self.life_awarded_flash = 0.0;
+        self.dispatcher = None;
+
+        // Keep the run so it can be watched again from the main menu
+        if let Some(replay_state) = data.world.try_fetch::<ReplayState>() {
+            if let ReplayState::Recording(replay) = &*replay_state {
+                let saved = replay_path().and_then(|path| Ok(replay.save(&path)?));
+                if let Err(e) = saved {
+                    println!("Failed to save replay: {}", e);
+                }
+            }
+        }
 
-        // Delete gameplay entities
-        let entities = data.world.read_resource::<EntitiesRes>();
-        let removal_tags = data.world.read_storage::<BreakoutRemovalTag>();
+        delete_gameplay_entities(data.world);
+    }
+}
 
-        let deletions_successful = (&entities, &removal_tags)
-            .join()
-            .map(|(entity, _)| entities.delete(entity))
-            .all(|x| x.is_ok());
+fn delete_gameplay_entities(world: &mut World) {
+    let entities = world.read_resource::<EntitiesRes>();
+    let removal_tags = world.read_storage::<BreakoutRemovalTag>();
 
-        if !deletions_successful {
-            println!("Failed to delete level");
-        }
+    let deletions_successful = (&entities, &removal_tags)
+        .join()
+        .map(|(entity, _)| entities.delete(entity))
+        .all(|x| x.is_ok());
+
+    if !deletions_successful {
+        println!("Failed to delete level");
     }
 }
 
@@ -243,23 +377,12 @@ fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
 }
 
 /// Initialises the paddle
-fn initialise_paddle(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) {
+fn initialise_paddle(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>, game: &Game) {
     let mut transform = Transform::default();
 
-    // Load configs
-    let (paddle_height, paddle_width, paddle_velocity) = {
-        let config = world.read_resource::<PaddleConfig>();
-        (config.height, config.width, config.velocity)
-    };
-
-    let (arena_width, arena_paddle_pos) = {
-        let config = world.read_resource::<ArenaConfig>();
-        (config.width, config.paddlepos)
-    };
-
     // Correctly position the paddle.
-    let y = arena_paddle_pos;
-    transform.set_translation_xyz((paddle_width * 0.5) + (arena_width * 0.5), y, 0.1);
+    let position = game.paddle.position;
+    transform.set_translation_xyz(position.x, position.y, 0.1);
 
     // Assign the sprite for the paddle
     let sprite_render = SpriteRender {
@@ -271,35 +394,15 @@ fn initialise_paddle(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>
     world
         .create_entity()
         .with(sprite_render)
-        .with(Paddle {
-            velocity: paddle_velocity,
-            width: paddle_width,
-            height: paddle_height,
-        })
+        .with(Paddle)
+        .with(Interpolated::new(position))
         .with(transform)
         .with(BreakoutRemovalTag)
         .build();
 }
 
 /// Initialises the ball
-fn initialise_ball(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) {
-    let mut transform = Transform::default();
-
-    // Load configs
-    let ball_radius = {
-        let config = world.read_resource::<BallConfig>();
-        config.radius
-    };
-
-    let (arena_width, arena_paddle_pos) = {
-        let config = world.read_resource::<ArenaConfig>();
-        (config.width, config.paddlepos)
-    };
-
-    // Correctly position the ball.
-    let y = arena_paddle_pos + ball_radius;
-    transform.set_translation_xyz(ball_radius + (arena_width * 0.5), y, 0.0);
-
+fn initialise_ball(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>, game: &Game) {
     // Assign the sprites for the ball
     let sprite_render = SpriteRender {
         sprite_sheet: sprite_sheet_handle,
@@ -307,43 +410,23 @@ fn initialise_ball(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>)
     };
 
     // Create a ball entity.
-    world
-        .create_entity()
-        .with(sprite_render)
-        .with(Ball {
-            radius: ball_radius,
-            has_launched: false,
-            velocity: Vector2::new(0.0, 0.0),
-            last_position: Vector2::new(0.0, 0.0),
-        })
-        .with(transform)
-        .with(BreakoutRemovalTag)
-        .build();
+    for ball in &game.balls {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(ball.position.x, ball.position.y, 0.0);
+
+        world
+            .create_entity()
+            .with(sprite_render.clone())
+            .with(Ball { id: ball.id })
+            .with(Interpolated::new(ball.position))
+            .with(transform)
+            .with(BreakoutRemovalTag)
+            .build();
+    }
 }
 
 /// Initialises a brick
-fn initialise_level(
-    world: &mut World,
-    sprite_sheet_handle: Handle<SpriteSheet>,
-    level_index: usize,
-    block_count: &mut i32,
-) {
-    // Load configs
-    let block_positions = {
-        let config = world.read_resource::<LevelsConfig>();
-        config.layout.to_vec()
-    };
-
-    let (block_width, block_height, block_damage_states) = {
-        let config = world.read_resource::<BlockConfig>();
-        (config.width, config.height, config.damage_states.to_vec())
-    };
-
-    let (_, arena_height) = {
-        let config = world.read_resource::<ArenaConfig>();
-        (config.width, config.height)
-    };
-
+fn initialise_level(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>, game: &Game) {
     // Assign the sprites for the block
     let mut sprite_render = SpriteRender {
         sprite_sheet: sprite_sheet_handle,
@@ -351,45 +434,18 @@ fn initialise_level(
     };
 
     // Create a block entities.
-    for y_pos in 0..block_positions[level_index].len() {
-        for x_pos in 0..block_positions[level_index][y_pos].len() {
-            if block_positions[level_index][y_pos][x_pos] == 0 {
-                continue;
-            }
-
-            let mut transform = Transform::default();
-
-            let x = block_width * x_pos as f32;
-            let y = block_height * y_pos as f32;
-
-            let hits = block_positions[level_index][y_pos][x_pos];
-
-            transform.set_translation_xyz(
-                (block_width * 0.5) + x,
-                (arena_height - y) - (block_height * 0.5),
-                0.1,
-            );
-
-            let block = Block {
-                width: block_width,
-                height: block_height,
-                max_hits: hits,
-                cur_hits: 0,
-                cur_damage_state: (hits - 1) as usize,
-            };
-
-            sprite_render.sprite_number = block_damage_states[block.cur_damage_state].0;
-
-            // increment block counter
-            *block_count += 1;
-
-            world
-                .create_entity()
-                .with(sprite_render.clone())
-                .with(block)
-                .with(transform)
-                .with(BreakoutRemovalTag)
-                .build();
-        }
+    for block in &game.blocks {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(block.position.x, block.position.y, 0.1);
+
+        sprite_render.sprite_number = block.sprite(&game.config.block);
+
+        world
+            .create_entity()
+            .with(sprite_render.clone())
+            .with(Block { id: block.id })
+            .with(transform)
+            .with(BreakoutRemovalTag)
+            .build();
     }
 }
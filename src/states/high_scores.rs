@@ -0,0 +1,194 @@
+use crate::config::LevelsConfig;
+use crate::data::GameMode;
+use crate::states::MainMenu;
+use crate::util::*;
+
+use breakout_core::high_scores::{HighScore, HighScores};
+use breakout_core::scoring::format_score;
+
+use amethyst::{
+    ecs::prelude::{Entity, WorldExt},
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+    ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText},
+};
+
+const BUTTON_BACK: &str = "high_scores_back";
+const BUTTON_LEVEL_UP: &str = "high_scores_level_up";
+const BUTTON_LEVEL_DN: &str = "high_scores_level_down";
+const TEXT_LEVEL: &str = "high_scores_level";
+const TEXT_LIST: &str = "high_scores_list";
+
+/// Lists the high scores of campaigns started on each level, one level at a time
+#[derive(Default, Debug)]
+pub struct HighScoresMenu {
+    ui_root: Option<Entity>,
+    button_back: Option<Entity>,
+    button_level_up: Option<Entity>,
+    button_level_down: Option<Entity>,
+    text_level: Option<Entity>,
+    text_list: Option<Entity>,
+    high_scores: HighScores,
+    level_index: usize,
+}
+
+impl HighScoresMenu {
+    pub fn new(level_index: usize) -> Self {
+        HighScoresMenu {
+            level_index,
+            ..HighScoresMenu::default()
+        }
+    }
+}
+
+impl SimpleState for HighScoresMenu {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+
+        self.ui_root =
+            Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/high_scores.ron", ())));
+
+        self.high_scores = match high_scores_path().and_then(|path| Ok(HighScores::load(&path)?)) {
+            Ok(high_scores) => high_scores,
+            Err(e) => {
+                println!("Failed to load high scores: {}", e);
+                HighScores::default()
+            }
+        };
+    }
+
+    fn handle_event(
+        &mut self,
+        state_data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        let StateData { world, .. } = state_data;
+
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) {
+                    Trans::Quit
+                } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                    Trans::Switch(Box::new(MainMenu::default()))
+                } else {
+                    Trans::None
+                }
+            }
+
+            StateEvent::Ui(UiEvent {
+                event_type: UiEventType::Click,
+                target,
+            }) => {
+                if Some(target) == self.button_back {
+                    return Trans::Switch(Box::new(MainMenu::default()));
+                }
+
+                if Some(target) == self.button_level_down && self.level_index > 0 {
+                    self.level_index -= 1;
+                }
+
+                if Some(target) == self.button_level_up {
+                    let levels_config = &world.read_resource::<LevelsConfig>().levels;
+                    self.level_index = (self.level_index + 1).min(levels_config.len() - 1);
+                }
+
+                Trans::None
+            }
+
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, state_data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        // only search for buttons if they have not been found yet
+        let StateData { world, .. } = state_data;
+
+        if self.button_back.is_none()
+            || self.button_level_up.is_none()
+            || self.button_level_down.is_none()
+            || self.text_level.is_none()
+            || self.text_list.is_none()
+        {
+            world.exec(|ui_finder: UiFinder<'_>| {
+                self.button_back = ui_finder.find(BUTTON_BACK);
+                self.button_level_up = ui_finder.find(BUTTON_LEVEL_UP);
+                self.button_level_down = ui_finder.find(BUTTON_LEVEL_DN);
+                self.text_level = ui_finder.find(TEXT_LEVEL);
+                self.text_list = ui_finder.find(TEXT_LIST);
+            });
+        }
+
+        let levels_config = world.read_resource::<LevelsConfig>();
+        let mut ui_text = world.write_storage::<UiText>();
+        if let Some(text) = self.text_level.and_then(|entity| ui_text.get_mut(entity)) {
+            text.text = levels_config.name(self.level_index);
+        }
+
+        if let Some(text) = self.text_list.and_then(|entity| ui_text.get_mut(entity)) {
+            let table = self.high_scores.table(GameMode::Campaign, self.level_index);
+
+            text.text = if table.is_empty() {
+                "NO SCORES YET".to_string()
+            } else {
+                table
+                    .iter()
+                    .enumerate()
+                    .map(|(place, entry)| format_entry(place, entry))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+        }
+
+        Trans::None
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        // after destroying the current UI, invalidate references as well (makes things cleaner)
+        if let Some(entity) = self.ui_root {
+            delete_hierarchy(entity, data.world).expect("Failed to remove HighScoresMenu");
+        }
+        self.ui_root = None;
+        self.button_back = None;
+        self.button_level_up = None;
+        self.button_level_down = None;
+        self.text_level = None;
+        self.text_list = None;
+    }
+}
+
+fn format_entry(place: usize, entry: &HighScore) -> String {
+    let seconds = entry.time as u64;
+
+    format!(
+        "{:>2}. {:<3} {:>7}  LV {}  {}:{:02}  {}",
+        place + 1,
+        entry.name,
+        format_score(entry.score),
+        entry.level_reached,
+        seconds / 60,
+        seconds % 60,
+        format_date(entry.date)
+    )
+}
+
+/// Seconds since the Unix epoch as a year-month-day date, in UTC
+fn format_date(timestamp: u64) -> String {
+    // Counting from 0000-03-01 puts the leap day at the end of the year
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{}-{:02}-{:02}", year, month, day)
+}
@@ -0,0 +1,98 @@
+use crate::util::*;
+
+use amethyst::{
+    core::Time,
+    ecs::prelude::{Entity, WorldExt},
+    input::is_close_requested,
+    prelude::*,
+    ui::{UiCreator, UiFinder, UiText},
+};
+
+const TEXT_CLEARED: &str = "cleared_text";
+const TEXT_NEXT_LEVEL: &str = "next_level_text";
+
+// Seconds the interstitial stays up before play resumes
+const DISPLAY_SECONDS: f32 = 2.0;
+
+/// Shown over the game between two levels of a campaign. The next level is already set up
+/// underneath, it starts once this pops.
+#[derive(Default, Debug)]
+pub struct LevelComplete {
+    ui_root: Option<Entity>,
+    text_cleared: Option<Entity>,
+    text_next_level: Option<Entity>,
+    cleared_level: usize,
+    next_level: usize,
+    elapsed: f32,
+}
+
+impl LevelComplete {
+    pub fn new(cleared_level: usize, next_level: usize) -> Self {
+        LevelComplete {
+            cleared_level,
+            next_level,
+            ..LevelComplete::default()
+        }
+    }
+}
+
+impl SimpleState for LevelComplete {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+
+        self.elapsed = 0.0;
+        self.ui_root = Some(
+            world.exec(|mut creator: UiCreator<'_>| creator.create("ui/level_complete.ron", ())),
+        );
+    }
+
+    fn handle_event(
+        &mut self,
+        _: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        match event {
+            StateEvent::Window(event) if is_close_requested(&event) => Trans::Quit,
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, state_data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let StateData { world, .. } = state_data;
+
+        // only search for the labels if they have not been found yet
+        if self.text_cleared.is_none() || self.text_next_level.is_none() {
+            world.exec(|ui_finder: UiFinder<'_>| {
+                self.text_cleared = ui_finder.find(TEXT_CLEARED);
+                self.text_next_level = ui_finder.find(TEXT_NEXT_LEVEL);
+            });
+        }
+
+        {
+            let mut ui_text = world.write_storage::<UiText>();
+            if let Some(text) = self.text_cleared.and_then(|e| ui_text.get_mut(e)) {
+                text.text = format!("LEVEL {} CLEARED", self.cleared_level);
+            }
+            if let Some(text) = self.text_next_level.and_then(|e| ui_text.get_mut(e)) {
+                text.text = format!("GET READY FOR LEVEL {}", self.next_level);
+            }
+        }
+
+        self.elapsed += world.read_resource::<Time>().delta_seconds();
+        if self.elapsed >= DISPLAY_SECONDS {
+            Trans::Pop
+        } else {
+            Trans::None
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        // after destroying the current UI, invalidate references as well (makes things cleaner)
+        if let Some(entity) = self.ui_root {
+            delete_hierarchy(entity, data.world).expect("Failed to remove LevelComplete");
+        }
+        self.ui_root = None;
+        self.text_cleared = None;
+        self.text_next_level = None;
+    }
+}
@@ -1,9 +1,12 @@
 extern crate amethyst;
 
-use crate::config::LevelsConfig;
-use crate::states::Breakout;
+use crate::config::{BreakoutConfig, LevelsConfig};
+use crate::data::{GameMode, GameSession};
+use crate::states::{Breakout, HighScoresMenu};
 use crate::util::*;
 
+use breakout_core::Replay;
+
 use std::cmp;
 
 use amethyst::{
@@ -17,6 +20,8 @@ const BUTTON_START: &str = "start";
 const BUTTON_LEVEL_UP: &str = "level_up";
 const BUTTON_LEVEL_DN: &str = "level_down";
 const BUTTON_LEVEL_INDEX: &str = "level_index_text";
+const BUTTON_REPLAY: &str = "replay";
+const BUTTON_HIGH_SCORES: &str = "high_scores";
 
 #[derive(Default, Debug)]
 pub struct MainMenu {
@@ -24,6 +29,8 @@ pub struct MainMenu {
     button_start: Option<Entity>,
     button_level_up: Option<Entity>,
     button_level_down: Option<Entity>,
+    button_replay: Option<Entity>,
+    button_high_scores: Option<Entity>,
     text_level_index: Option<Entity>,
     level_index: usize,
 }
@@ -59,32 +66,36 @@ impl SimpleState for MainMenu {
                 target,
             }) => {
                 if Some(target) == self.button_start {
-                    return Trans::Switch(Box::new(Breakout::new(self.level_index)));
+                    world.insert(GameSession::new(GameMode::Campaign, self.level_index));
+                    return Trans::Switch(Box::new(Breakout::default()));
+                }
+
+                if Some(target) == self.button_high_scores {
+                    return Trans::Switch(Box::new(HighScoresMenu::new(self.level_index)));
                 }
 
-                let old_index = self.level_index;
+                if Some(target) == self.button_replay {
+                    return match load_replay(world) {
+                        Ok(replay) => {
+                            world.insert(GameSession::new(GameMode::Replay, replay.level));
+                            Trans::Switch(Box::new(Breakout::replay(replay)))
+                        }
+                        Err(e) => {
+                            println!("Cannot play the last replay: {}", e);
+                            Trans::None
+                        }
+                    };
+                }
 
                 if Some(target) == self.button_level_down && self.level_index > 0 {
                     self.level_index = cmp::max(self.level_index - 1, 0);
                 }
 
                 if Some(target) == self.button_level_up {
-                    let levels_config = &world.read_resource::<LevelsConfig>().layout;
+                    let levels_config = &world.read_resource::<LevelsConfig>().levels;
                     self.level_index = cmp::min(self.level_index + 1, levels_config.len() - 1);
                 }
 
-                if old_index != self.level_index {
-                    let mut ui_text = world.write_storage::<UiText>();
-                    {
-                        if let Some(text) = self
-                            .text_level_index
-                            .and_then(|entity| ui_text.get_mut(entity))
-                        {
-                            text.text = self.level_index.to_string();
-                        }
-                    }
-                }
-
                 Trans::None
             }
 
@@ -99,16 +110,29 @@ impl SimpleState for MainMenu {
         if self.button_start.is_none()
             || self.button_level_up.is_none()
             || self.button_level_down.is_none()
+            || self.button_replay.is_none()
+            || self.button_high_scores.is_none()
             || self.text_level_index.is_none()
         {
             world.exec(|ui_finder: UiFinder<'_>| {
                 self.button_start = ui_finder.find(BUTTON_START);
                 self.button_level_up = ui_finder.find(BUTTON_LEVEL_UP);
                 self.button_level_down = ui_finder.find(BUTTON_LEVEL_DN);
+                self.button_replay = ui_finder.find(BUTTON_REPLAY);
+                self.button_high_scores = ui_finder.find(BUTTON_HIGH_SCORES);
                 self.text_level_index = ui_finder.find(BUTTON_LEVEL_INDEX);
             });
         }
 
+        let levels_config = world.read_resource::<LevelsConfig>();
+        let mut ui_text = world.write_storage::<UiText>();
+        if let Some(text) = self
+            .text_level_index
+            .and_then(|entity| ui_text.get_mut(entity))
+        {
+            text.text = levels_config.name(self.level_index);
+        }
+
         Trans::None
     }
 
@@ -121,7 +145,20 @@ impl SimpleState for MainMenu {
         self.button_start = None;
         self.button_level_up = None;
         self.button_level_down = None;
+        self.button_replay = None;
+        self.button_high_scores = None;
         self.text_level_index = None;
         self.level_index = 0;
     }
 }
+
+/// Loads the replay of the last run, as long as it still plays back with the loaded config
+fn load_replay(world: &World) -> amethyst::Result<Replay> {
+    let replay = Replay::load(&replay_path()?)?;
+
+    let config = world.read_resource::<BreakoutConfig>();
+    let levels = world.read_resource::<LevelsConfig>();
+    replay.start(config.clone(), &levels)?;
+
+    Ok(replay)
+}
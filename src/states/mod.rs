@@ -1,6 +1,11 @@
 mod breakout;
+mod high_scores;
+mod level_complete;
 mod main_menu;
 mod pause;
 mod results;
 
-pub use self::{breakout::Breakout, main_menu::MainMenu, pause::PauseMenu, results::Results};
+pub use self::{
+    breakout::Breakout, high_scores::HighScoresMenu, level_complete::LevelComplete,
+    main_menu::MainMenu, pause::PauseMenu, results::Results,
+};
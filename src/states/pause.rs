@@ -1,7 +1,9 @@
-use crate::data::ScoreBoard;
+use crate::data::GameSession;
 use crate::states::MainMenu;
 use crate::util::*;
 
+use breakout_core::scoring::format_score;
+
 use amethyst::{
     ecs::prelude::{Entity, WorldExt},
     input::{is_close_requested, is_key_down, VirtualKeyCode},
@@ -95,7 +97,7 @@ impl SimpleState for PauseMenu {
             });
         }
 
-        let score_board = &world.read_resource::<ScoreBoard>();
+        let session = &world.read_resource::<GameSession>();
         let mut ui_text = world.write_storage::<UiText>();
         {
             if let Some(text) = self
@@ -104,11 +106,7 @@ impl SimpleState for PauseMenu {
             {
                 let score_string = "SCORE: ";
 
-                text.text = format!(
-                    "{}{}",
-                    score_string,
-                    (score_board.current_score * 100).to_string()
-                );
+                text.text = format!("{}{}", score_string, format_score(session.score));
             }
         }
 
@@ -1,19 +1,35 @@
 extern crate amethyst;
 
-use crate::data::ScoreBoard;
+use crate::config::{LevelsConfig, SimulationConfig};
+use crate::data::{GameMode, GameSession};
 use crate::states::MainMenu;
 use crate::util::*;
 
+use breakout_core::high_scores::{HighScore, HighScores, TABLE_SIZE};
+use breakout_core::scoring::format_score;
+use breakout_core::Outcome;
+
 use amethyst::{
     ecs::prelude::{Entity, WorldExt},
-    input::is_close_requested,
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
     prelude::*,
     ui::{UiCreator, UiEvent, UiEventType, UiFinder, UiText},
+    winit::{Event, WindowEvent},
 };
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
 const BUTTON_QUIT: &str = "game_quit";
 const BUTTON_QUIT_TO_MENU: &str = "level_quit_to_menu";
 const TEXT_LEVEL_INDEX: &str = "score_text";
+const TEXT_OUTCOME: &str = "outcome_text";
+const TEXT_INITIALS: &str = "initials_text";
+const TEXT_LEVEL_STATS: &str = "level_stats_text";
+
+const MAX_INITIALS: usize = 3;
+
+// Levels listed under the totals, the latest ones when a run played more than fit
+const MAX_LEVEL_STATS: usize = 7;
 
 #[derive(Default, Debug)]
 pub struct Results {
@@ -21,6 +37,70 @@ pub struct Results {
     button_quit_to_menu: Option<Entity>,
     button_quit_app: Option<Entity>,
     text_score: Option<Entity>,
+    text_outcome: Option<Entity>,
+    text_initials: Option<Entity>,
+    text_level_stats: Option<Entity>,
+    // How the run went on each level it played, one line per level
+    level_stats: String,
+    // Loaded when the run was one that goes in the high score tables
+    high_scores: Option<HighScores>,
+    entering_initials: bool,
+    initials: String,
+    // Where the run landed in its high score table once the initials are in
+    place: Option<usize>,
+}
+
+impl Results {
+    // Types the player's initials in, and files the score once they press enter
+    fn edit_initials(&mut self, event: &Event, world: &World) {
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::ReceivedCharacter(c),
+                ..
+            } if c.is_ascii_alphanumeric() && self.initials.len() < MAX_INITIALS => {
+                self.initials.push(c.to_ascii_uppercase());
+            }
+
+            _ if is_key_down(event, VirtualKeyCode::Back) => {
+                self.initials.pop();
+            }
+
+            _ if is_key_down(event, VirtualKeyCode::Return) && !self.initials.is_empty() => {
+                self.save_high_score(world);
+            }
+
+            _ => {}
+        }
+    }
+
+    fn save_high_score(&mut self, world: &World) {
+        self.entering_initials = false;
+
+        let high_scores = match self.high_scores.as_mut() {
+            Some(high_scores) => high_scores,
+            None => return,
+        };
+
+        let session = world.read_resource::<GameSession>();
+        let tick_seconds = world.read_resource::<SimulationConfig>().tick_seconds();
+        let entry = HighScore {
+            name: self.initials.clone(),
+            score: session.score,
+            date: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or(0),
+            level_reached: session.current_level,
+            time: session.ticks() as f32 * tick_seconds,
+        };
+
+        self.place = high_scores.insert(session.mode, session.starting_level, entry);
+
+        let saved = high_scores_path().and_then(|path| Ok(high_scores.save(&path)?));
+        if let Err(e) = saved {
+            println!("Failed to save high scores: {}", e);
+        }
+    }
 }
 
 impl SimpleState for Results {
@@ -29,11 +109,42 @@ impl SimpleState for Results {
 
         self.ui_root =
             Some(world.exec(|mut creator: UiCreator<'_>| creator.create("ui/results.ron", ())));
+
+        let session = world.read_resource::<GameSession>();
+        let tick_seconds = world.read_resource::<SimulationConfig>().tick_seconds();
+        let levels_config = world.read_resource::<LevelsConfig>();
+        let skipped = session.levels.len().saturating_sub(MAX_LEVEL_STATS);
+
+        self.level_stats = session.levels[skipped..]
+            .iter()
+            .map(|stats| {
+                format!(
+                    "{}: {}, {} LIVES LOST, {:.1}S",
+                    levels_config.name(stats.level),
+                    format_score(stats.score),
+                    stats.lives_lost,
+                    stats.ticks as f32 * tick_seconds
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // Replays were scored when they were played, only campaign runs make the tables
+        if session.mode == GameMode::Campaign {
+            match high_scores_path().and_then(|path| Ok(HighScores::load(&path)?)) {
+                Ok(high_scores) => {
+                    self.entering_initials =
+                        high_scores.qualifies(session.mode, session.starting_level, session.score);
+                    self.high_scores = Some(high_scores);
+                }
+                Err(e) => println!("Failed to load high scores: {}", e),
+            }
+        }
     }
 
     fn handle_event(
         &mut self,
-        _: StateData<'_, GameData<'_, '_>>,
+        state_data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
         match event {
@@ -41,6 +152,9 @@ impl SimpleState for Results {
                 if is_close_requested(&event) {
                     Trans::Quit
                 } else {
+                    if self.entering_initials {
+                        self.edit_initials(&event, state_data.world);
+                    }
                     Trans::None
                 }
             }
@@ -69,15 +183,21 @@ impl SimpleState for Results {
         if self.button_quit_app.is_none()
             || self.button_quit_to_menu.is_none()
             || self.text_score.is_none()
+            || self.text_outcome.is_none()
+            || self.text_initials.is_none()
+            || self.text_level_stats.is_none()
         {
             world.exec(|ui_finder: UiFinder<'_>| {
                 self.button_quit_app = ui_finder.find(BUTTON_QUIT);
                 self.button_quit_to_menu = ui_finder.find(BUTTON_QUIT_TO_MENU);
                 self.text_score = ui_finder.find(TEXT_LEVEL_INDEX);
+                self.text_outcome = ui_finder.find(TEXT_OUTCOME);
+                self.text_initials = ui_finder.find(TEXT_INITIALS);
+                self.text_level_stats = ui_finder.find(TEXT_LEVEL_STATS);
             });
         }
 
-        let score_board = &world.read_resource::<ScoreBoard>();
+        let session = &world.read_resource::<GameSession>();
         let mut ui_text = world.write_storage::<UiText>();
         {
             if let Some(text) = self
@@ -86,11 +206,46 @@ impl SimpleState for Results {
             {
                 let score_string = "SCORE: ";
 
-                text.text = format!(
-                    "{}{}",
-                    score_string,
-                    (score_board.current_score * 100).to_string()
-                );
+                text.text = format!("{}{}", score_string, format_score(session.score));
+            }
+
+            if let Some(text) = self
+                .text_outcome
+                .and_then(|entity: Entity| ui_text.get_mut(entity))
+            {
+                text.text = match (session.mode, session.outcome) {
+                    (_, Outcome::Cleared) => "YOU WIN".to_string(),
+                    (_, Outcome::OutOfLives) => "GAME OVER".to_string(),
+                    // A replay of a run that was quit part way through
+                    (GameMode::Replay, Outcome::Playing) => "REPLAY OVER".to_string(),
+                    (GameMode::Campaign, Outcome::Playing) => {
+                        format!("LEVEL {}", session.current_level)
+                    }
+                };
+            }
+
+            if let Some(text) = self
+                .text_initials
+                .and_then(|entity: Entity| ui_text.get_mut(entity))
+            {
+                text.text = if self.entering_initials {
+                    format!(
+                        "NEW HIGH SCORE: {:_<width$}",
+                        self.initials,
+                        width = MAX_INITIALS
+                    )
+                } else if let Some(place) = self.place {
+                    format!("HIGH SCORE #{} OF {}", place + 1, TABLE_SIZE)
+                } else {
+                    String::new()
+                };
+            }
+
+            if let Some(text) = self
+                .text_level_stats
+                .and_then(|entity: Entity| ui_text.get_mut(entity))
+            {
+                text.text = self.level_stats.clone();
             }
         }
 
@@ -106,5 +261,13 @@ impl SimpleState for Results {
         self.button_quit_app = None;
         self.button_quit_to_menu = None;
         self.text_score = None;
+        self.text_outcome = None;
+        self.text_initials = None;
+        self.text_level_stats = None;
+        self.level_stats.clear();
+        self.high_scores = None;
+        self.entering_initials = false;
+        self.initials.clear();
+        self.place = None;
     }
 }
@@ -1,19 +1,22 @@
 use crate::components::Block;
-use crate::config::BlockConfig;
 use crate::data::PauseState;
 
+use breakout_core::Game;
+
 use amethyst::{
     derive::SystemDesc,
-    ecs::prelude::{Join, Read, ResourceId, System, SystemData, World, WriteStorage},
+    ecs::prelude::{
+        Join, Read, ReadExpect, ReadStorage, ResourceId, System, SystemData, World, WriteStorage,
+    },
     renderer::SpriteRender,
 };
 
 #[derive(SystemData)]
 pub struct BlockStateSystemData<'s> {
-    pub blocks: WriteStorage<'s, Block>,
+    pub game: ReadExpect<'s, Game>,
+    pub blocks: ReadStorage<'s, Block>,
     pub renderers: WriteStorage<'s, SpriteRender>,
     pub pause_state: Read<'s, PauseState>,
-    pub block_config: Read<'s, BlockConfig>,
 }
 
 /// This system is responsible for managing block damage state visuals
@@ -25,27 +28,20 @@ impl<'s> System<'s> for BlockStateSystem {
 
     fn run(&mut self, system_data: Self::SystemData) {
         let BlockStateSystemData {
-            mut blocks,
+            game,
+            blocks,
             mut renderers,
             pause_state,
-            block_config,
         } = system_data;
 
         if pause_state.paused {
             return;
         }
 
-        for (block, renderer) in (&mut blocks, &mut renderers).join() {
-            let (_, damage_threshold) = block_config.damage_states[block.cur_damage_state];
-
-            // change block sprite index based on damage thresholds
-            if (block.max_hits - block.cur_hits) < damage_threshold
-                && block.cur_hits != block.max_hits
-            {
-                block.cur_damage_state = (block.cur_damage_state - 1).max(0);
-
-                let (sprite_index, _) = block_config.damage_states[block.cur_damage_state];
-                renderer.sprite_number = sprite_index;
+        // change block sprite index to the one its block type has for the hits it has taken
+        for (block, renderer) in (&blocks, &mut renderers).join() {
+            if let Some(b) = game.blocks.iter().find(|b| b.id == block.id) {
+                renderer.sprite_number = b.sprite(&game.config.block);
             }
         }
     }
@@ -0,0 +1,53 @@
+use crate::data::PauseState;
+use crate::events::Collision;
+
+use breakout_core::{Game, GameEvent};
+
+use amethyst::{
+    derive::SystemDesc,
+    ecs::prelude::{Read, ReadExpect, ResourceId, System, SystemData, World, Write},
+    shrev::EventChannel,
+};
+
+#[derive(SystemData)]
+pub struct CollisionSystemData<'s> {
+    pub game: ReadExpect<'s, Game>,
+    pub pause_state: Read<'s, PauseState>,
+    pub collision_events: Write<'s, EventChannel<Collision>>,
+}
+
+/// This system is responsible for publishing the contacts of the last step on the
+/// `Collision` event channel
+#[derive(SystemDesc)]
+pub struct CollisionSystem;
+
+impl<'s> System<'s> for CollisionSystem {
+    type SystemData = CollisionSystemData<'s>;
+
+    fn run(&mut self, system_data: Self::SystemData) {
+        let CollisionSystemData {
+            game,
+            pause_state,
+            mut collision_events,
+        } = system_data;
+
+        if pause_state.paused {
+            return;
+        }
+
+        collision_events.iter_write(game.events.iter().filter_map(|event| match *event {
+            GameEvent::Collision {
+                ball,
+                surface,
+                normal,
+                point,
+            } => Some(Collision {
+                ball,
+                surface,
+                normal,
+                point,
+            }),
+            _ => None,
+        }));
+    }
+}
@@ -0,0 +1,42 @@
+use crate::components::Interpolated;
+use crate::data::FixedTimestep;
+
+use amethyst::{
+    core::Transform,
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, ReadStorage, ResourceId, System, SystemData, World, WriteStorage},
+};
+
+#[derive(SystemData)]
+pub struct InterpolationSystemData<'s> {
+    pub interpolated: ReadStorage<'s, Interpolated>,
+    pub transforms: WriteStorage<'s, Transform>,
+    pub fixed_timestep: Read<'s, FixedTimestep>,
+}
+
+/// This system runs once per rendered frame and places moving entities between their last
+/// two simulated positions, so motion stays smooth when the frame rate and tick rate differ
+#[derive(SystemDesc)]
+pub struct InterpolationSystem;
+
+impl<'s> System<'s> for InterpolationSystem {
+    type SystemData = InterpolationSystemData<'s>;
+
+    fn run(&mut self, system_data: Self::SystemData) {
+        let InterpolationSystemData {
+            interpolated,
+            mut transforms,
+            fixed_timestep,
+        } = system_data;
+
+        let alpha = fixed_timestep.alpha();
+
+        for (interpolated, transform) in (&interpolated, &mut transforms).join() {
+            let position =
+                interpolated.previous + (interpolated.current - interpolated.previous) * alpha;
+
+            transform.set_translation_x(position.x);
+            transform.set_translation_y(position.y);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::components::{BreakoutRemovalTag, Interpolated, Projectile};
+use crate::data::{BreakoutSprites, PauseState};
+
+use breakout_core::Game;
+
+use amethyst::{
+    core::{math::Vector3, Transform},
+    derive::SystemDesc,
+    ecs::prelude::{
+        Entities, Join, Read, ReadExpect, ResourceId, System, SystemData, World, WriteStorage,
+    },
+    renderer::SpriteRender,
+};
+
+use std::collections::HashSet;
+
+// Size of the ball sprite the projectiles are drawn with
+const PROJECTILE_SPRITE_SIZE: (f32, f32) = (9.0, 10.0);
+
+#[derive(SystemData)]
+pub struct LaserSystemData<'s> {
+    pub entities: Entities<'s>,
+    pub game: ReadExpect<'s, Game>,
+    pub projectiles: WriteStorage<'s, Projectile>,
+    pub transforms: WriteStorage<'s, Transform>,
+    pub interpolated: WriteStorage<'s, Interpolated>,
+    pub renderers: WriteStorage<'s, SpriteRender>,
+    pub removal_tags: WriteStorage<'s, BreakoutRemovalTag>,
+    pub sprites: ReadExpect<'s, BreakoutSprites>,
+    pub pause_state: Read<'s, PauseState>,
+}
+
+/// This system is responsible for drawing the shots fired by the paddle's laser
+#[derive(SystemDesc)]
+pub struct LaserSystem;
+
+impl<'s> System<'s> for LaserSystem {
+    type SystemData = LaserSystemData<'s>;
+
+    fn run(&mut self, system_data: Self::SystemData) {
+        let LaserSystemData {
+            entities,
+            game,
+            mut projectiles,
+            mut transforms,
+            mut interpolated,
+            mut renderers,
+            mut removal_tags,
+            sprites,
+            pause_state,
+        } = system_data;
+
+        if pause_state.paused {
+            return;
+        }
+
+        // Drop the shots that hit something or left the arena and move the rest
+        let mut existing = HashSet::new();
+        for (e, projectile, transform) in (&entities, &projectiles, &mut transforms).join() {
+            match game.projectiles.iter().find(|p| p.id == projectile.id) {
+                Some(p) => {
+                    transform.set_translation_x(p.position.x);
+                    transform.set_translation_y(p.position.y);
+                    existing.insert(projectile.id);
+                }
+                None => entities.delete(e).expect("entity deleted"),
+            }
+        }
+
+        for p in game
+            .projectiles
+            .iter()
+            .filter(|p| !existing.contains(&p.id))
+        {
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(p.position.x, p.position.y, 0.05);
+            transform.set_scale(Vector3::new(
+                p.width / PROJECTILE_SPRITE_SIZE.0,
+                p.height / PROJECTILE_SPRITE_SIZE.1,
+                1.0,
+            ));
+
+            entities
+                .build_entity()
+                .with(Projectile { id: p.id }, &mut projectiles)
+                .with(Interpolated::new(p.position), &mut interpolated)
+                .with(transform, &mut transforms)
+                .with(
+                    SpriteRender {
+                        sprite_sheet: sprites.sprite_sheet.clone(),
+                        sprite_number: game.config.laser.sprite,
+                    },
+                    &mut renderers,
+                )
+                .with(BreakoutRemovalTag, &mut removal_tags)
+                .build();
+        }
+    }
+}
@@ -1,10 +1,23 @@
 mod block;
 mod block_state;
-mod bounce;
+mod collision;
+mod interpolation;
+mod laser;
 mod move_ball;
 mod paddle;
+mod power_up;
+mod step;
+mod tick;
 
 pub use self::{
-    block::BlockSystem, block_state::BlockStateSystem, bounce::BounceSystem,
-    move_ball::MoveBallSystem, paddle::PaddleSystem,
+    block::BlockSystem,
+    block_state::BlockStateSystem,
+    collision::CollisionSystem,
+    interpolation::InterpolationSystem,
+    laser::LaserSystem,
+    move_ball::MoveBallSystem,
+    paddle::PaddleSystem,
+    power_up::PowerUpSystem,
+    step::StepSystemDesc,
+    tick::{TickBeginSystem, TickEndSystem},
 };
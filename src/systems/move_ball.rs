@@ -1,27 +1,36 @@
-use crate::components::{Ball, Paddle};
-use crate::data::{LevelInfo, PauseState};
+use crate::components::{Ball, BreakoutRemovalTag, Interpolated};
+use crate::data::{BallSpeed, BreakoutSprites, PauseState};
+
+use breakout_core::Game;
 
 use amethyst::{
-    core::{Time, Transform},
+    core::Transform,
     derive::SystemDesc,
     ecs::prelude::{
-        Join, Read, ReadStorage, ResourceId, System, SystemData, World, Write, WriteStorage,
+        Entities, Join, Read, ReadExpect, ResourceId, System, SystemData, World, Write,
+        WriteStorage,
     },
-    input::{InputHandler, StringBindings},
+    renderer::SpriteRender,
 };
 
+use std::collections::HashSet;
+
 #[derive(SystemData)]
 pub struct MoveBallSystemData<'s> {
+    pub entities: Entities<'s>,
+    pub game: ReadExpect<'s, Game>,
     pub balls: WriteStorage<'s, Ball>,
     pub transforms: WriteStorage<'s, Transform>,
-    pub paddles: ReadStorage<'s, Paddle>,
-    pub input: Read<'s, InputHandler<StringBindings>>,
-    pub time: Read<'s, Time>,
+    pub interpolated: WriteStorage<'s, Interpolated>,
+    pub renderers: WriteStorage<'s, SpriteRender>,
+    pub removal_tags: WriteStorage<'s, BreakoutRemovalTag>,
+    pub sprites: ReadExpect<'s, BreakoutSprites>,
     pub pause_state: Read<'s, PauseState>,
-    pub level_info: Write<'s, LevelInfo>,
+    pub ball_speed: Write<'s, BallSpeed>,
 }
 
-/// This system is responsible for moving all the balls
+/// This system is responsible for keeping a ball entity for every ball in play, placed
+/// where the game moved it
 #[derive(SystemDesc)]
 pub struct MoveBallSystem;
 
@@ -30,59 +39,56 @@ impl<'s> System<'s> for MoveBallSystem {
 
     fn run(&mut self, system_data: Self::SystemData) {
         let MoveBallSystemData {
+            entities,
+            game,
             mut balls,
             mut transforms,
-            paddles,
-            input,
-            time,
+            mut interpolated,
+            mut renderers,
+            mut removal_tags,
+            sprites,
             pause_state,
-            mut level_info,
+            mut ball_speed,
         } = system_data;
 
         if pause_state.paused {
             return;
         }
 
-        let mut paddle_x = 0.0;
-        let mut paddle_y = 0.0;
-        for (_, paddle_transform) in (&paddles, &transforms).join() {
-            paddle_x = paddle_transform.translation().x;
-            paddle_y = paddle_transform.translation().y;
-        }
-
-        // Iterate over all balls and move them according to their velocity.
-        for (ball, transform) in (&mut balls, &mut transforms).join() {
-            let opt_launch = input.action_is_down("launch_ball").unwrap_or(false);
-
-            ball.last_position.x = transform.translation().x;
-            ball.last_position.y = transform.translation().y;
+        *ball_speed = game.ball_speed.clone();
 
-            if !ball.has_launched {
-                if opt_launch {
-                    println!("Launch Ball!");
-                    ball.velocity.x = 300.0;
-                    ball.velocity.y = 300.0;
-                    ball.has_launched = true;
-                } else {
-                    transform.set_translation_x(paddle_x);
-                    transform.set_translation_y(paddle_y + ball.radius);
+        // Drop the entities of balls that left play and move the rest
+        let mut existing = HashSet::new();
+        for (e, ball, transform) in (&entities, &balls, &mut transforms).join() {
+            match game.balls.iter().find(|b| b.id == ball.id) {
+                Some(b) => {
+                    transform.set_translation_x(b.position.x);
+                    transform.set_translation_y(b.position.y);
+                    existing.insert(ball.id);
                 }
+                None => entities.delete(e).expect("entity deleted"),
             }
+        }
 
-            if ball.has_launched {
-                transform.prepend_translation_x(ball.velocity.x * time.delta_seconds());
-                transform.prepend_translation_y(ball.velocity.y * time.delta_seconds());
-
-                let ball_y = transform.translation().y;
+        // Balls put into play this tick, e.g. by the multi ball power up
+        for b in game.balls.iter().filter(|b| !existing.contains(&b.id)) {
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(b.position.x, b.position.y, 0.0);
 
-                if ball_y < ball.radius {
-                    level_info.num_lives_remaining = (level_info.num_lives_remaining - 1).max(0);
-                    ball.velocity.x = 0.0;
-                    ball.velocity.y = 0.0;
-                    ball.has_launched = false;
-                    println!("Died!");
-                }
-            }
+            entities
+                .build_entity()
+                .with(Ball { id: b.id }, &mut balls)
+                .with(Interpolated::new(b.position), &mut interpolated)
+                .with(transform, &mut transforms)
+                .with(
+                    SpriteRender {
+                        sprite_sheet: sprites.sprite_sheet.clone(),
+                        sprite_number: 1, // ball is the second sprite in the sprite sheet
+                    },
+                    &mut renderers,
+                )
+                .with(BreakoutRemovalTag, &mut removal_tags)
+                .build();
         }
     }
 }
@@ -1,26 +1,26 @@
 use crate::components::Paddle;
-use crate::config::ArenaConfig;
 use crate::data::PauseState;
 
+use breakout_core::Game;
+
 use amethyst::{
-    core::{Time, Transform},
+    core::{math::Vector3, Transform},
     derive::SystemDesc,
-    ecs::prelude::{Join, Read, ReadStorage, ResourceId, System, SystemData, World, WriteStorage},
-    input::{InputHandler, StringBindings},
+    ecs::prelude::{
+        Join, Read, ReadExpect, ReadStorage, ResourceId, System, SystemData, World, WriteStorage,
+    },
 };
 
 #[derive(SystemData)]
 pub struct PaddleSystemData<'s> {
+    pub game: ReadExpect<'s, Game>,
     pub paddles: ReadStorage<'s, Paddle>,
     pub transforms: WriteStorage<'s, Transform>,
-    pub time: Read<'s, Time>,
-    pub input: Read<'s, InputHandler<StringBindings>>,
-    pub arena_config: Read<'s, ArenaConfig>,
     pub pause_state: Read<'s, PauseState>,
 }
 
-/// This system is responsible for moving all the paddles according to the user
-/// provided input.
+/// This system is responsible for placing the paddle where the game moved it and sizing it
+/// for the wide paddle power up
 #[derive(SystemDesc)]
 pub struct PaddleSystem;
 
@@ -29,11 +29,9 @@ impl<'s> System<'s> for PaddleSystem {
 
     fn run(&mut self, system_data: Self::SystemData) {
         let PaddleSystemData {
+            game,
             paddles,
             mut transforms,
-            time,
-            input,
-            arena_config,
             pause_state,
         } = system_data;
 
@@ -41,25 +39,13 @@ impl<'s> System<'s> for PaddleSystem {
             return;
         }
 
-        // Iterate over all paddles and move them according to the input the user
-        // provided.
-        for (paddle, transform) in (&paddles, &mut transforms).join() {
-            let opt_movement = input.axis_value("paddle");
-
-            if let Some(movement) = opt_movement {
-                let arena_width = arena_config.width;
-                let scaled_move = paddle.velocity * time.delta_seconds() * movement as f32;
-
-                transform.prepend_translation_x(scaled_move);
+        let paddle = &game.paddle;
+        let scale = paddle.width / game.config.paddle.width;
 
-                // We make sure the paddle remains in the arena.
-                let paddle_x = transform.translation().x;
-                transform.set_translation_x(
-                    paddle_x
-                        .max(paddle.width * 0.5)
-                        .min(arena_width - paddle.width * 0.5),
-                );
-            }
+        for (_, transform) in (&paddles, &mut transforms).join() {
+            transform.set_translation_x(paddle.position.x);
+            transform.set_translation_y(paddle.position.y);
+            transform.set_scale(Vector3::new(scale, 1.0, 1.0));
         }
     }
 }
@@ -0,0 +1,103 @@
+use crate::components::{BreakoutRemovalTag, Capsule, Interpolated};
+use crate::data::{BreakoutSprites, PauseState};
+
+use breakout_core::Game;
+
+use amethyst::{
+    core::{math::Vector3, Transform},
+    derive::SystemDesc,
+    ecs::prelude::{
+        Entities, Join, Read, ReadExpect, ResourceId, System, SystemData, World, WriteStorage,
+    },
+    renderer::SpriteRender,
+};
+
+use std::collections::HashSet;
+
+#[derive(SystemData)]
+pub struct PowerUpSystemData<'s> {
+    pub entities: Entities<'s>,
+    pub game: ReadExpect<'s, Game>,
+    pub capsules: WriteStorage<'s, Capsule>,
+    pub transforms: WriteStorage<'s, Transform>,
+    pub interpolated: WriteStorage<'s, Interpolated>,
+    pub renderers: WriteStorage<'s, SpriteRender>,
+    pub removal_tags: WriteStorage<'s, BreakoutRemovalTag>,
+    pub sprites: ReadExpect<'s, BreakoutSprites>,
+    pub pause_state: Read<'s, PauseState>,
+}
+
+/// This system is responsible for drawing the power up capsules falling from destroyed
+/// blocks
+#[derive(SystemDesc)]
+pub struct PowerUpSystem;
+
+impl<'s> System<'s> for PowerUpSystem {
+    type SystemData = PowerUpSystemData<'s>;
+
+    fn run(&mut self, system_data: Self::SystemData) {
+        let PowerUpSystemData {
+            entities,
+            game,
+            mut capsules,
+            mut transforms,
+            mut interpolated,
+            mut renderers,
+            mut removal_tags,
+            sprites,
+            pause_state,
+        } = system_data;
+
+        if pause_state.paused {
+            return;
+        }
+
+        // Drop the capsules that were caught or fell out and move the rest
+        let mut existing = HashSet::new();
+        for (e, capsule, transform) in (&entities, &capsules, &mut transforms).join() {
+            match game.capsules.iter().find(|c| c.id == capsule.id) {
+                Some(c) => {
+                    transform.set_translation_x(c.position.x);
+                    transform.set_translation_y(c.position.y);
+                    existing.insert(capsule.id);
+                }
+                None => entities.delete(e).expect("entity deleted"),
+            }
+        }
+
+        let power_up_config = &game.config.powerups;
+        let block_config = &game.config.block;
+
+        for c in game.capsules.iter().filter(|c| !existing.contains(&c.id)) {
+            // Level validation makes sure every kind of capsule has a drop entry
+            let sprite_number = match power_up_config.drop(c.kind) {
+                Some(drop) => drop.sprite,
+                None => continue,
+            };
+
+            // Capsules reuse the block sprites, scaled down to the capsule size
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(c.position.x, c.position.y, 0.05);
+            transform.set_scale(Vector3::new(
+                c.width / block_config.width,
+                c.height / block_config.height,
+                1.0,
+            ));
+
+            entities
+                .build_entity()
+                .with(Capsule { id: c.id }, &mut capsules)
+                .with(Interpolated::new(c.position), &mut interpolated)
+                .with(transform, &mut transforms)
+                .with(
+                    SpriteRender {
+                        sprite_sheet: sprites.sprite_sheet.clone(),
+                        sprite_number,
+                    },
+                    &mut renderers,
+                )
+                .with(BreakoutRemovalTag, &mut removal_tags)
+                .build();
+        }
+    }
+}
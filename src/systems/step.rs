@@ -0,0 +1,88 @@
+use crate::data::{FixedTimestep, PauseState, ReplayState};
+use crate::events::SpawnBall;
+
+use breakout_core::{BallSpawn, Game, Input};
+
+use amethyst::{
+    derive::SystemDesc,
+    ecs::prelude::{Read, ResourceId, System, SystemData, World, WriteExpect},
+    input::{InputHandler, StringBindings},
+    shrev::{EventChannel, ReaderId},
+};
+
+use std::collections::VecDeque;
+
+#[derive(SystemData)]
+pub struct StepSystemData<'s> {
+    pub game: WriteExpect<'s, Game>,
+    pub input: Read<'s, InputHandler<StringBindings>>,
+    pub fixed_timestep: Read<'s, FixedTimestep>,
+    pub pause_state: Read<'s, PauseState>,
+    pub replay_state: WriteExpect<'s, ReplayState>,
+    pub spawn_events: Read<'s, EventChannel<SpawnBall>>,
+}
+
+/// This system runs the game rules for one fixed tick with the player's current input, or
+/// the recorded input when watching a replay. The systems after it bring the entities in
+/// line with the result.
+#[derive(SystemDesc)]
+#[system_desc(name(StepSystemDesc))]
+pub struct StepSystem {
+    #[system_desc(event_channel_reader)]
+    spawn_reader: ReaderId<SpawnBall>,
+    // Balls waiting to be put into play, one each tick so that they are part of the input
+    #[system_desc(skip)]
+    pending_spawns: VecDeque<BallSpawn>,
+}
+
+impl StepSystem {
+    pub fn new(spawn_reader: ReaderId<SpawnBall>) -> Self {
+        StepSystem {
+            spawn_reader,
+            pending_spawns: VecDeque::new(),
+        }
+    }
+}
+
+impl<'s> System<'s> for StepSystem {
+    type SystemData = StepSystemData<'s>;
+
+    fn run(&mut self, system_data: Self::SystemData) {
+        let StepSystemData {
+            mut game,
+            input,
+            fixed_timestep,
+            pause_state,
+            mut replay_state,
+            spawn_events,
+        } = system_data;
+
+        if pause_state.paused {
+            return;
+        }
+
+        let spawns = spawn_events.read(&mut self.spawn_reader);
+
+        let tick_input = match &mut *replay_state {
+            ReplayState::Recording(replay) => {
+                self.pending_spawns.extend(spawns.map(|spawn| BallSpawn {
+                    position: spawn.position.into(),
+                    velocity: spawn.velocity.into(),
+                }));
+
+                let tick_input = Input {
+                    paddle: input.axis_value("paddle").unwrap_or(0.0),
+                    launch_ball: input.action_is_down("launch_ball").unwrap_or(false),
+                    fire: input.action_is_down("fire").unwrap_or(false),
+                    spawn_ball: self.pending_spawns.pop_front(),
+                };
+                replay.record(tick_input);
+                tick_input
+            }
+            // The replay already has the balls that were put into play when it was recorded
+            ReplayState::Playing(player) => player.next_input().unwrap_or_default(),
+        };
+
+        game.step(&tick_input, fixed_timestep.tick_seconds);
+    }
+}
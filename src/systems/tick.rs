@@ -0,0 +1,63 @@
+use crate::components::Interpolated;
+
+use amethyst::{
+    core::Transform,
+    derive::SystemDesc,
+    ecs::prelude::{Join, ReadStorage, ResourceId, System, SystemData, World, WriteStorage},
+};
+
+#[derive(SystemData)]
+pub struct TickBeginSystemData<'s> {
+    pub interpolated: WriteStorage<'s, Interpolated>,
+    pub transforms: WriteStorage<'s, Transform>,
+}
+
+/// This system runs first in every fixed tick. The transforms of moving entities may have
+/// been blended for rendering since the last tick, so their true simulation positions are
+/// put back before anything reads them.
+#[derive(SystemDesc)]
+pub struct TickBeginSystem;
+
+impl<'s> System<'s> for TickBeginSystem {
+    type SystemData = TickBeginSystemData<'s>;
+
+    fn run(&mut self, system_data: Self::SystemData) {
+        let TickBeginSystemData {
+            mut interpolated,
+            mut transforms,
+        } = system_data;
+
+        for (interpolated, transform) in (&mut interpolated, &mut transforms).join() {
+            interpolated.previous = interpolated.current;
+
+            transform.set_translation_x(interpolated.current.x);
+            transform.set_translation_y(interpolated.current.y);
+        }
+    }
+}
+
+#[derive(SystemData)]
+pub struct TickEndSystemData<'s> {
+    pub interpolated: WriteStorage<'s, Interpolated>,
+    pub transforms: ReadStorage<'s, Transform>,
+}
+
+/// This system runs last in every fixed tick and records where the simulation left each
+/// moving entity
+#[derive(SystemDesc)]
+pub struct TickEndSystem;
+
+impl<'s> System<'s> for TickEndSystem {
+    type SystemData = TickEndSystemData<'s>;
+
+    fn run(&mut self, system_data: Self::SystemData) {
+        let TickEndSystemData {
+            mut interpolated,
+            transforms,
+        } = system_data;
+
+        for (interpolated, transform) in (&mut interpolated, &transforms).join() {
+            interpolated.current = transform.translation().xy();
+        }
+    }
+}
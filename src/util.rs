@@ -1,13 +1,13 @@
-use float_cmp::*;
-
 use amethyst::{
-    core::math::*,
     ecs::{
         error::WrongGeneration,
         prelude::{Entity, World, WorldExt},
     },
+    utils::application_root_dir,
 };
 
+use std::{env, path::PathBuf};
+
 /// delete the specified root entity and all of its descendents as specified
 /// by the Parent component and maintained by the ParentHierarchy resource
 // from https://github.com/amethyst/evoli src/utils/hierarchy_util.rs
@@ -15,58 +15,33 @@ pub fn delete_hierarchy(root: Entity, world: &mut World) -> Result<(), WrongGene
     world.delete_entity(root)
 }
 
-// A point is in a box when its coordinates are smaller or equal than the top
-// right and larger or equal than the bottom left.
-pub fn point_in_rect(x: f32, y: f32, left: f32, bottom: f32, right: f32, top: f32) -> bool {
-    x >= left && x <= right && y >= bottom && y <= top
-}
-
-// Checks to see if two line segments are parallel
-pub fn is_vector_parallel(
-    a: Vector2<f32>,
-    b: Vector2<f32>,
-    c: Vector2<f32>,
-    d: Vector2<f32>,
-) -> bool {
-    let line_ab = a - b;
-    let line_cd = c - d;
-
-    approx_eq!(f32, line_ab.dot(&line_cd), 1.0)
+/// Where the replay of the last run played is kept
+pub fn replay_path() -> amethyst::Result<PathBuf> {
+    Ok(application_root_dir()?.join("replays").join("last.ron"))
 }
 
-// Checks to see if two line segments intersect. It is assumed parallels are checked elsewhere
-pub fn is_line_intersected(
-    a: Vector2<f32>,
-    b: Vector2<f32>,
-    c: Vector2<f32>,
-    d: Vector2<f32>,
-) -> bool {
-    // lines the same
-    if approx_eq!(f32, a.x, c.x)
-        && approx_eq!(f32, a.y, c.y)
-        && approx_eq!(f32, b.x, d.x)
-        && approx_eq!(f32, b.y, d.y)
-    {
-        return true;
-    }
-
-    // do intersection test
-    let ta = ((c.y - d.y) * (a.x - c.x) + (d.x - c.x) * (a.y - c.y))
-        / ((d.x - c.x) * (a.y - b.y) - (a.x - b.x) * (d.y - c.y));
-
-    // ta intersection is outside the bounds of the line segments
-    if ta > 1.0 || ta < 0.0 {
-        return false;
-    }
-
-    let tb = ((a.y - b.y) * (a.x - c.x) + (b.x - a.x) * (a.y - c.y))
-        / ((d.x - c.x) * (a.y - b.y) - (a.x - b.x) * (d.y - c.y));
-
-    // tb intersection is outside the bounds of the line segments
-    if tb > 1.0 || ta < 0.0 {
-        return false;
+/// Where the game keeps the files that belong to the player rather than the install, such
+/// as the high scores
+pub fn user_data_dir() -> amethyst::Result<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+
+    let dir = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home.map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|home| home.join(".local").join("share")))
+    };
+
+    match dir {
+        Some(dir) => Ok(dir.join("breakout")),
+        // Nowhere better to put them, keep them next to the game
+        None => Ok(application_root_dir()?.join("data")),
     }
+}
 
-    // ta and tb are within their respective line segments, thus they intersect
-    true
+pub fn high_scores_path() -> amethyst::Result<PathBuf> {
+    Ok(user_data_dir()?.join("high_scores.ron"))
 }